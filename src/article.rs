@@ -1,19 +1,217 @@
-use eure::{FromEure, value::Text};
+use std::fmt;
+
+use chrono::{DateTime, FixedOffset, NaiveDate, NaiveDateTime, NaiveTime};
+use eure::{
+    FromEure,
+    document::parse::{ParseContext, ParseError, ParseErrorKind},
+    value::Text,
+};
 use indexmap::IndexMap;
 
-/// Alert type for GitHub-style alert boxes
-#[derive(Debug, Clone, Copy, PartialEq, FromEure)]
+use crate::diagram::Diagram;
+
+/// Alert type for GitHub-style alert boxes: one of the GitHub types or a
+/// kind declared under `alerts` in `site.eure`
+#[derive(Debug, Clone, PartialEq)]
 pub enum AlertType {
-    #[eure(rename = "NOTE")]
     Note,
-    #[eure(rename = "TIP")]
     Tip,
-    #[eure(rename = "IMPORTANT")]
     Important,
-    #[eure(rename = "WARNING")]
     Warning,
-    #[eure(rename = "CAUTION")]
     Caution,
+    Custom(String),
+}
+
+impl FromEure<'_> for AlertType {
+    type Error = ParseError;
+
+    fn parse(ctx: &ParseContext<'_>) -> Result<Self, Self::Error> {
+        let kind: &str = ctx.parse()?;
+        Ok(match kind {
+            "NOTE" => AlertType::Note,
+            "TIP" => AlertType::Tip,
+            "IMPORTANT" => AlertType::Important,
+            "WARNING" => AlertType::Warning,
+            "CAUTION" => AlertType::Caution,
+            other => AlertType::Custom(other.to_string()),
+        })
+    }
+}
+
+/// License that articles may be published under, identified by its SPDX identifier
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum LicenseId {
+    CcBy40,
+    CcBySa40,
+    Cc010,
+    Mit,
+    Apache20,
+    Mpl20,
+}
+
+impl LicenseId {
+    pub const ALL: [LicenseId; 6] = [
+        LicenseId::CcBy40,
+        LicenseId::CcBySa40,
+        LicenseId::Cc010,
+        LicenseId::Mit,
+        LicenseId::Apache20,
+        LicenseId::Mpl20,
+    ];
+
+    /// License of an SPDX identifier, matched case-insensitively as SPDX does
+    pub fn from_spdx_id(id: &str) -> Option<LicenseId> {
+        Self::ALL
+            .into_iter()
+            .find(|license| license.spdx_id().eq_ignore_ascii_case(id))
+    }
+
+    pub fn spdx_id(&self) -> &'static str {
+        match self {
+            LicenseId::CcBy40 => "CC-BY-4.0",
+            LicenseId::CcBySa40 => "CC-BY-SA-4.0",
+            LicenseId::Cc010 => "CC0-1.0",
+            LicenseId::Mit => "MIT",
+            LicenseId::Apache20 => "Apache-2.0",
+            LicenseId::Mpl20 => "MPL-2.0",
+        }
+    }
+
+    pub fn name(&self) -> &'static str {
+        match self {
+            LicenseId::CcBy40 => "Creative Commons Attribution 4.0 International",
+            LicenseId::CcBySa40 => "Creative Commons Attribution-ShareAlike 4.0 International",
+            LicenseId::Cc010 => "CC0 1.0 Universal",
+            LicenseId::Mit => "MIT License",
+            LicenseId::Apache20 => "Apache License 2.0",
+            LicenseId::Mpl20 => "Mozilla Public License 2.0",
+        }
+    }
+
+    pub fn url(&self) -> &'static str {
+        match self {
+            LicenseId::CcBy40 => "https://creativecommons.org/licenses/by/4.0/",
+            LicenseId::CcBySa40 => "https://creativecommons.org/licenses/by-sa/4.0/",
+            LicenseId::Cc010 => "https://creativecommons.org/publicdomain/zero/1.0/",
+            LicenseId::Mit => "https://opensource.org/license/mit",
+            LicenseId::Apache20 => "https://www.apache.org/licenses/LICENSE-2.0",
+            LicenseId::Mpl20 => "https://www.mozilla.org/en-US/MPL/2.0/",
+        }
+    }
+}
+
+/// License of an article: an SPDX license expression such as `CC-BY-4.0` or
+/// `MIT OR Apache-2.0`. Expressions combine the licenses of `LicenseId` with
+/// `AND`, `OR` and parentheses; `WITH` exceptions are not supported.
+#[derive(Debug, Clone, PartialEq)]
+pub struct License {
+    terms: Vec<LicenseTerm>,
+}
+
+/// Token of a license expression
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum LicenseTerm {
+    Id(LicenseId),
+    And,
+    Or,
+    Open,
+    Close,
+}
+
+impl License {
+    pub fn parse_str(expression: &str) -> Result<Self, String> {
+        let spaced = expression.replace('(', " ( ").replace(')', " ) ");
+        let terms = spaced
+            .split_whitespace()
+            .map(|token| match token {
+                "AND" => Ok(LicenseTerm::And),
+                "OR" => Ok(LicenseTerm::Or),
+                "(" => Ok(LicenseTerm::Open),
+                ")" => Ok(LicenseTerm::Close),
+                "WITH" => Err("license exceptions (WITH) are not supported".to_string()),
+                id => LicenseId::from_spdx_id(id).map(LicenseTerm::Id).ok_or_else(|| {
+                    let known: Vec<&str> = LicenseId::ALL.iter().map(|id| id.spdx_id()).collect();
+                    format!("unknown license {} (expected one of {})", id, known.join(", "))
+                }),
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        // Licenses and closing parentheses must alternate with operators
+        // and opening parentheses, with balanced parentheses
+        let mut depth = 0usize;
+        let mut expect_license = true;
+        for term in &terms {
+            match (term, expect_license) {
+                (LicenseTerm::Id(_), true) => expect_license = false,
+                (LicenseTerm::Open, true) => depth += 1,
+                (LicenseTerm::Close, false) if depth > 0 => depth -= 1,
+                (LicenseTerm::And | LicenseTerm::Or, false) => expect_license = true,
+                _ => {
+                    return Err(format!(
+                        "'{}' is not a valid license expression",
+                        expression.trim()
+                    ));
+                }
+            }
+        }
+        if expect_license || depth > 0 {
+            return Err(format!(
+                "'{}' is not a valid license expression",
+                expression.trim()
+            ));
+        }
+        Ok(License { terms })
+    }
+
+    pub fn terms(&self) -> &[LicenseTerm] {
+        &self.terms
+    }
+
+    /// Licenses named in the expression, in order
+    pub fn ids(&self) -> impl Iterator<Item = LicenseId> + '_ {
+        self.terms.iter().filter_map(|term| match term {
+            LicenseTerm::Id(id) => Some(*id),
+            _ => None,
+        })
+    }
+}
+
+/// The expression with canonical identifiers, such as `MIT OR Apache-2.0`
+impl fmt::Display for License {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let mut previous = None;
+        for term in &self.terms {
+            let separator = !matches!(previous, None | Some(LicenseTerm::Open))
+                && *term != LicenseTerm::Close;
+            if separator {
+                f.write_str(" ")?;
+            }
+            match term {
+                LicenseTerm::Id(id) => f.write_str(id.spdx_id())?,
+                LicenseTerm::And => f.write_str("AND")?,
+                LicenseTerm::Or => f.write_str("OR")?,
+                LicenseTerm::Open => f.write_str("(")?,
+                LicenseTerm::Close => f.write_str(")")?,
+            }
+            previous = Some(*term);
+        }
+        Ok(())
+    }
+}
+
+impl FromEure<'_> for License {
+    type Error = ParseError;
+
+    fn parse(ctx: &ParseContext<'_>) -> Result<Self, Self::Error> {
+        let text: &str = ctx.parse()?;
+        License::parse_str(text).map_err(|reason| ParseError {
+            node_id: ctx.node_id(),
+            kind: ParseErrorKind::InvalidPattern {
+                kind: "SPDX license expression".to_string(),
+                reason,
+            },
+        })
+    }
 }
 
 /// Mark options for extending text content with visual markers
@@ -21,14 +219,64 @@ pub enum AlertType {
 pub struct MarkOptions {
     #[eure(default)]
     pub alert: Option<AlertType>,
+    /// Title replacing the default title of the alert type
+    #[eure(rename = "alert-title", default)]
+    pub alert_title: Option<Text>,
     #[eure(rename = "dangerously-inner-html", default)]
     pub dangerously_inner_html: bool,
+    /// Use this block as the article excerpt
+    #[eure(default)]
+    pub excerpt: bool,
+    /// Render the JSON equivalent next to an Eure code block
+    #[eure(rename = "show-json", default)]
+    pub show_json: bool,
+    /// Intentional counter-example: the Eure code block must fail to parse,
+    /// or fail validation when it names a schema
+    #[eure(rename = "expect-invalid", default)]
+    pub expect_invalid: bool,
+    /// Schema that an Eure code block must validate against: a sibling
+    /// block key, a section key path or a file path inside the repository
+    #[eure(default)]
+    pub schema: Option<String>,
+    /// Render an Eure code block with the parser and schema validator
+    /// diagnostics annotated inline
+    #[eure(rename = "show-diagnostics", default)]
+    pub show_diagnostics: bool,
+    /// Diagnostics an Eure code block must produce, exactly: each entry is a
+    /// message, optionally followed by the line it starts on as `(line 7)`
+    #[eure(rename = "expect-diagnostics", default)]
+    pub expect_diagnostics: Vec<String>,
+    /// Replace the block with a file of the repository, optionally narrowed to
+    /// a line range or a named region: `path#L10-L30` or `path#region`
+    #[eure(default)]
+    pub include: Option<String>,
+    /// Collapse the block behind a summary line
+    #[eure(default)]
+    pub details: Option<Details>,
+}
+
+/// Mark options of a section
+#[derive(Debug, Clone, PartialEq, FromEure, Default)]
+pub struct SectionMark {
+    /// Collapse the content of the section behind a summary line
+    #[eure(default)]
+    pub details: Option<Details>,
+}
+
+/// Collapsible content rendered as `<details>`, closed unless `open` is set
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Details {
+    pub summary: Text,
+    #[eure(default)]
+    pub open: bool,
 }
 
 #[derive(Debug, Clone, PartialEq, FromEure)]
 pub struct Article {
     #[eure(ext)]
     pub frontmatter: Frontmatter,
+    #[eure(ext, default)]
+    pub license: Option<License>,
     #[eure(rename = "#")]
     pub header: Text,
     #[eure(flatten)]
@@ -38,13 +286,123 @@ pub struct Article {
 #[derive(Debug, Clone, PartialEq, FromEure)]
 pub struct Frontmatter {
     pub title: Text,
-    pub description: Text,
+    /// Falls back to the generated excerpt when omitted
+    #[eure(default)]
+    pub description: Option<Text>,
     #[eure(default)]
-    pub date: Option<Text>,
+    pub date: Option<ArticleDate>,
     #[eure(default)]
     pub tags: Vec<String>,
     #[eure(default)]
     pub draft: bool,
+    #[eure(default)]
+    pub series: Option<SeriesRef>,
+    #[eure(default)]
+    pub cover: Option<Cover>,
+}
+
+/// Cover image stored in the article's asset directory
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Cover {
+    /// Path relative to `articles/<slug>/`
+    pub src: String,
+    pub alt: Text,
+}
+
+/// Publication date, optionally with a time of day and UTC offset.
+///
+/// Accepts `2026-01-28`, `2026-01-28T09:30`, `2026-01-28T09:30:00` and
+/// RFC 3339 timestamps such as `2026-01-28T09:30:00+09:00`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ArticleDate {
+    pub date: NaiveDate,
+    pub time: Option<NaiveTime>,
+    pub offset: Option<FixedOffset>,
+}
+
+impl ArticleDate {
+    pub fn parse_str(s: &str) -> Option<Self> {
+        let s = s.trim();
+        if let Ok(date) = NaiveDate::parse_from_str(s, "%Y-%m-%d") {
+            return Some(Self {
+                date,
+                time: None,
+                offset: None,
+            });
+        }
+        if let Ok(datetime) = DateTime::parse_from_rfc3339(s) {
+            return Some(Self {
+                date: datetime.date_naive(),
+                time: Some(datetime.time()),
+                offset: Some(*datetime.offset()),
+            });
+        }
+        ["%Y-%m-%dT%H:%M:%S", "%Y-%m-%dT%H:%M"]
+            .iter()
+            .find_map(|format| NaiveDateTime::parse_from_str(s, format).ok())
+            .map(|datetime| Self {
+                date: datetime.date(),
+                time: Some(datetime.time()),
+                offset: None,
+            })
+    }
+
+    /// Key for chronological ordering; timestamps with an offset are compared in UTC
+    pub fn sort_key(&self) -> NaiveDateTime {
+        let local = self.date.and_time(self.time.unwrap_or(NaiveTime::MIN));
+        match self.offset {
+            Some(offset) => local - offset,
+            None => local,
+        }
+    }
+
+    /// Human-readable date such as "January 28, 2026"
+    pub fn human(&self) -> String {
+        self.date.format("%B %-d, %Y").to_string()
+    }
+
+    /// Machine-readable value for the `datetime` attribute of `<time>`
+    pub fn datetime_attr(&self) -> String {
+        match (self.time, self.offset) {
+            (None, _) => self.date.format("%Y-%m-%d").to_string(),
+            (Some(time), None) => self.date.and_time(time).format("%Y-%m-%dT%H:%M:%S").to_string(),
+            (Some(time), Some(offset)) => self
+                .date
+                .and_time(time)
+                .and_local_timezone(offset)
+                .single()
+                .map(|datetime| datetime.to_rfc3339())
+                .unwrap_or_default(),
+        }
+    }
+}
+
+impl FromEure<'_> for ArticleDate {
+    type Error = ParseError;
+
+    fn parse(ctx: &ParseContext<'_>) -> Result<Self, Self::Error> {
+        let text: &str = ctx.parse()?;
+        ArticleDate::parse_str(text).ok_or_else(|| ParseError {
+            node_id: ctx.node_id(),
+            kind: ParseErrorKind::InvalidPattern {
+                kind: "date".to_string(),
+                reason: format!(
+                    "'{}' is not a date (YYYY-MM-DD) or date-time (YYYY-MM-DDTHH:MM[:SS][offset])",
+                    text
+                ),
+            },
+        })
+    }
+}
+
+/// Reference from an article to the series it belongs to
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct SeriesRef {
+    /// Series ID declared in `series.eure`
+    pub id: String,
+    /// Explicit part number; parts without one are ordered after numbered parts
+    #[eure(default)]
+    pub part: Option<u32>,
 }
 
 /// Entry for Table of Contents
@@ -52,7 +410,6 @@ pub struct Frontmatter {
 pub struct TocEntry {
     pub id: String,
     pub title: String,
-    #[allow(dead_code)]
     pub level: u8,
     pub children: Vec<TocEntry>,
 }
@@ -62,6 +419,11 @@ pub enum Item<T> {
     Normal(T),
     List(Vec<T>),
     Toc(Toc),
+    Figure(FigureBlock),
+    Footnote(FootnoteBlock),
+    Diagram(DiagramBlock),
+    CodeGroup(CodeGroupBlock),
+    Partial(PartialBlock<T>),
 }
 
 #[derive(Debug, Clone, PartialEq, FromEure)]
@@ -70,21 +432,92 @@ pub struct Toc {
     toc: bool,
 }
 
+/// Section item holding a `$figure`
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct FigureBlock {
+    #[eure(ext)]
+    pub figure: Figure,
+}
+
+/// Section holding a footnote body; the section key is the footnote key
+/// referenced as `[^key]` from the article text
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct FootnoteBlock {
+    #[eure(ext)]
+    pub footnote: Text,
+}
+
+/// Section holding a `$diagram`
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct DiagramBlock {
+    #[eure(ext)]
+    pub diagram: Diagram,
+}
+
+/// Section holding a `$code-group`: the same content in several languages,
+/// keyed by tab label and rendered as tabs
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct CodeGroupBlock {
+    #[eure(ext, rename = "code-group")]
+    pub code_group: IndexMap<String, Text>,
+    /// `$equivalent = true` fails the build unless every snippet holds the same data
+    #[eure(ext, default)]
+    pub equivalent: bool,
+}
+
+/// Section holding a `$partial`: the sections of `partials/<name>.eure`,
+/// rendered in place. Sections written next to `$partial` override the
+/// partial's sections of the same key; once partials are expanded,
+/// `sections` holds the merged result.
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct PartialBlock<T> {
+    #[eure(ext)]
+    pub partial: String,
+    #[eure(flatten)]
+    pub sections: IndexMap<String, Item<T>>,
+}
+
+/// Image with alt text and an optional caption, rendered as a numbered `<figure>`
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Figure {
+    /// Image path relative to the article's asset directory (`articles/<slug>/`)
+    pub src: String,
+    pub alt: Text,
+    #[eure(default)]
+    pub caption: Option<Text>,
+    #[eure(default)]
+    pub credit: Option<Text>,
+}
+
 #[derive(Debug, Clone, PartialEq, FromEure)]
 pub enum TextOrNested<T> {
     Text {
         #[eure(flatten)]
         text: Text,
-        #[eure(ext, default)]
-        mark: MarkOptions,
+        /// Boxed to keep the enum small; most blocks carry no marks
+        #[eure(ext, default, via = "Boxed")]
+        mark: Box<MarkOptions>,
     },
     Nested(T),
 }
 
+/// Parse strategy for boxed fields
+pub struct Boxed;
+
+impl<'doc, T: FromEure<'doc>> FromEure<'doc, Box<T>> for Boxed {
+    type Error = T::Error;
+
+    fn parse(ctx: &ParseContext<'doc>) -> Result<Box<T>, Self::Error> {
+        ctx.parse().map(Box::new)
+    }
+}
+
 #[derive(Debug, Clone, PartialEq, FromEure)]
 pub struct Level2 {
     #[eure(rename = "##")]
     pub header: Text,
+    #[eure(ext, default)]
+    pub mark: SectionMark,
     #[eure(flatten)]
     pub sections: IndexMap<String, Item<TextOrNested<Level3>>>,
 }
@@ -93,6 +526,8 @@ pub struct Level2 {
 pub struct Level3 {
     #[eure(rename = "###")]
     pub header: Text,
+    #[eure(ext, default)]
+    pub mark: SectionMark,
     #[eure(flatten)]
     pub sections: IndexMap<String, Item<TextOrNested<Level4>>>,
 }
@@ -101,6 +536,8 @@ pub struct Level3 {
 pub struct Level4 {
     #[eure(rename = "####")]
     pub header: Text,
+    #[eure(ext, default)]
+    pub mark: SectionMark,
     #[eure(flatten)]
     pub sections: IndexMap<String, Item<TextOrNested<Level5>>>,
 }
@@ -109,6 +546,8 @@ pub struct Level4 {
 pub struct Level5 {
     #[eure(rename = "#####")]
     pub header: Text,
+    #[eure(ext, default)]
+    pub mark: SectionMark,
     #[eure(flatten)]
     pub sections: IndexMap<String, Item<TextOrNested<Level6>>>,
 }
@@ -117,6 +556,381 @@ pub struct Level5 {
 pub struct Level6 {
     #[eure(rename = "######")]
     pub header: Text,
+    #[eure(ext, default)]
+    pub mark: SectionMark,
     #[eure(flatten)]
     pub sections: IndexMap<String, Item<Text>>,
 }
+
+/// A text block of the article body
+#[derive(Debug, Clone)]
+pub struct TextBlock<'a> {
+    /// Section key path such as `syntax.nesting.example`, with `[i]` for list items
+    pub path: String,
+    pub text: &'a Text,
+    /// `None` for blocks that cannot carry marks (children of level 6 sections)
+    pub mark: Option<&'a MarkOptions>,
+}
+
+/// Visitor over the blocks of an article body in document order
+pub trait Visit<'a> {
+    fn visit_text(&mut self, _block: TextBlock<'a>) {}
+    /// Visits the header and marks of a section before its items
+    fn visit_header(&mut self, _path: &str, _header: &'a Text, _mark: &'a SectionMark) {}
+    fn visit_figure(&mut self, _path: &str, _figure: &'a Figure) {}
+    fn visit_footnote(&mut self, _key: &'a str, _body: &'a Text) {}
+    fn visit_diagram(&mut self, _diagram: &'a Diagram) {}
+    /// Visits each snippet as a text block unless overridden
+    fn visit_code_group(&mut self, path: &str, block: &'a CodeGroupBlock) {
+        for (label, text) in &block.code_group {
+            self.visit_text(TextBlock {
+                path: format!("{}.$code-group.{}", path, label),
+                text,
+                mark: None,
+            });
+        }
+    }
+}
+
+/// Visitor over the blocks of an article body in document order that may
+/// rewrite them; an error stops the walk
+pub trait VisitMut {
+    type Error;
+
+    /// `mark` is `None` for blocks that cannot carry marks
+    fn visit_text(
+        &mut self,
+        _path: &str,
+        _text: &mut Text,
+        _mark: Option<&mut MarkOptions>,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    /// Visits the header and marks of a section before its items
+    fn visit_header(
+        &mut self,
+        _path: &str,
+        _header: &mut Text,
+        _mark: &mut SectionMark,
+    ) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn visit_figure(&mut self, _path: &str, _figure: &mut Figure) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    fn visit_footnote(&mut self, _key: &str, _body: &mut Text) -> Result<(), Self::Error> {
+        Ok(())
+    }
+    /// Visits each snippet as a text block unless overridden
+    fn visit_code_group(
+        &mut self,
+        path: &str,
+        block: &mut CodeGroupBlock,
+    ) -> Result<(), Self::Error> {
+        for (label, text) in &mut block.code_group {
+            self.visit_text(&format!("{}.$code-group.{}", path, label), text, None)?;
+        }
+        Ok(())
+    }
+}
+
+impl Article {
+    /// Walk the article body in document order
+    pub fn visit<'a>(&'a self, visitor: &mut impl Visit<'a>) {
+        walk_sections(&self.sections, "", visitor);
+    }
+
+    /// Collect every text block of the article body in document order
+    pub fn text_blocks(&self) -> Vec<TextBlock<'_>> {
+        struct Collect<'a>(Vec<TextBlock<'a>>);
+        impl<'a> Visit<'a> for Collect<'a> {
+            fn visit_text(&mut self, block: TextBlock<'a>) {
+                self.0.push(block);
+            }
+        }
+        let mut collect = Collect(Vec::new());
+        self.visit(&mut collect);
+        collect.0
+    }
+
+    /// Walk the article body in document order, letting the visitor rewrite
+    /// blocks. Stops at the first error.
+    pub fn visit_mut<V: VisitMut>(&mut self, visitor: &mut V) -> Result<(), V::Error> {
+        walk_sections_mut(&mut self.sections, "", visitor)
+    }
+
+    /// Collect every figure of the article body with its key path, in document order
+    pub fn figures(&self) -> Vec<(String, &Figure)> {
+        struct Collect<'a>(Vec<(String, &'a Figure)>);
+        impl<'a> Visit<'a> for Collect<'a> {
+            fn visit_figure(&mut self, path: &str, figure: &'a Figure) {
+                self.0.push((path.to_string(), figure));
+            }
+        }
+        let mut collect = Collect(Vec::new());
+        self.visit(&mut collect);
+        collect.0
+    }
+}
+
+fn walk_sections<'a, T: Walk>(
+    sections: &'a IndexMap<String, Item<T>>,
+    parent: &str,
+    visitor: &mut impl Visit<'a>,
+) {
+    for (key, item) in sections {
+        let path = if parent.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", parent, key)
+        };
+        match item {
+            Item::Normal(value) => value.walk(&path, visitor),
+            Item::List(values) => {
+                for (i, value) in values.iter().enumerate() {
+                    value.walk(&format!("{}[{}]", path, i), visitor);
+                }
+            }
+            Item::Toc(_) => {}
+            Item::Figure(block) => visitor.visit_figure(&path, &block.figure),
+            Item::Footnote(block) => visitor.visit_footnote(key, &block.footnote),
+            Item::Diagram(block) => visitor.visit_diagram(&block.diagram),
+            Item::CodeGroup(block) => visitor.visit_code_group(&path, block),
+            Item::Partial(block) => walk_sections(&block.sections, &path, visitor),
+        }
+    }
+}
+
+fn walk_sections_mut<T: WalkMut, V: VisitMut>(
+    sections: &mut IndexMap<String, Item<T>>,
+    parent: &str,
+    visitor: &mut V,
+) -> Result<(), V::Error> {
+    for (key, item) in sections {
+        let path = if parent.is_empty() {
+            key.clone()
+        } else {
+            format!("{}.{}", parent, key)
+        };
+        match item {
+            Item::Normal(value) => value.walk_mut(&path, visitor)?,
+            Item::List(values) => {
+                for (i, value) in values.iter_mut().enumerate() {
+                    value.walk_mut(&format!("{}[{}]", path, i), visitor)?;
+                }
+            }
+            Item::Toc(_) | Item::Diagram(_) => {}
+            Item::Figure(block) => visitor.visit_figure(&path, &mut block.figure)?,
+            Item::Footnote(block) => visitor.visit_footnote(key, &mut block.footnote)?,
+            Item::CodeGroup(block) => visitor.visit_code_group(&path, block)?,
+            Item::Partial(block) => walk_sections_mut(&mut block.sections, &path, visitor)?,
+        }
+    }
+    Ok(())
+}
+
+/// Trait for walking the blocks of nested sections mutably
+trait WalkMut {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error>;
+}
+
+/// Trait for walking the blocks of nested sections
+trait Walk {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>);
+}
+
+impl Walk for Text {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        visitor.visit_text(TextBlock {
+            path: path.to_string(),
+            text: self,
+            mark: None,
+        });
+    }
+}
+
+impl<T: Walk> Walk for TextOrNested<T> {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        match self {
+            TextOrNested::Text { text, mark } => visitor.visit_text(TextBlock {
+                path: path.to_string(),
+                text,
+                mark: Some(mark),
+            }),
+            TextOrNested::Nested(nested) => nested.walk(path, visitor),
+        }
+    }
+}
+
+impl Walk for Level2 {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        visitor.visit_header(path, &self.header, &self.mark);
+        walk_sections(&self.sections, path, visitor);
+    }
+}
+
+impl Walk for Level3 {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        visitor.visit_header(path, &self.header, &self.mark);
+        walk_sections(&self.sections, path, visitor);
+    }
+}
+
+impl Walk for Level4 {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        visitor.visit_header(path, &self.header, &self.mark);
+        walk_sections(&self.sections, path, visitor);
+    }
+}
+
+impl Walk for Level5 {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        visitor.visit_header(path, &self.header, &self.mark);
+        walk_sections(&self.sections, path, visitor);
+    }
+}
+
+impl Walk for Level6 {
+    fn walk<'a>(&'a self, path: &str, visitor: &mut impl Visit<'a>) {
+        visitor.visit_header(path, &self.header, &self.mark);
+        walk_sections(&self.sections, path, visitor);
+    }
+}
+
+impl WalkMut for Text {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        visitor.visit_text(path, self, None)
+    }
+}
+
+impl<T: WalkMut> WalkMut for TextOrNested<T> {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        match self {
+            TextOrNested::Text { text, mark } => visitor.visit_text(path, text, Some(mark)),
+            TextOrNested::Nested(nested) => nested.walk_mut(path, visitor),
+        }
+    }
+}
+
+impl WalkMut for Level2 {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        visitor.visit_header(path, &mut self.header, &mut self.mark)?;
+        walk_sections_mut(&mut self.sections, path, visitor)
+    }
+}
+
+impl WalkMut for Level3 {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        visitor.visit_header(path, &mut self.header, &mut self.mark)?;
+        walk_sections_mut(&mut self.sections, path, visitor)
+    }
+}
+
+impl WalkMut for Level4 {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        visitor.visit_header(path, &mut self.header, &mut self.mark)?;
+        walk_sections_mut(&mut self.sections, path, visitor)
+    }
+}
+
+impl WalkMut for Level5 {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        visitor.visit_header(path, &mut self.header, &mut self.mark)?;
+        walk_sections_mut(&mut self.sections, path, visitor)
+    }
+}
+
+impl WalkMut for Level6 {
+    fn walk_mut<V: VisitMut>(&mut self, path: &str, visitor: &mut V) -> Result<(), V::Error> {
+        visitor.visit_header(path, &mut self.header, &mut self.mark)?;
+        walk_sections_mut(&mut self.sections, path, visitor)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn date(s: &str) -> ArticleDate {
+        ArticleDate::parse_str(s).unwrap()
+    }
+
+    #[test]
+    fn parses_dates_and_date_times() {
+        let day = date("2026-01-28");
+        assert_eq!(day.date, NaiveDate::from_ymd_opt(2026, 1, 28).unwrap());
+        assert_eq!((day.time, day.offset), (None, None));
+        assert_eq!(day.human(), "January 28, 2026");
+        assert_eq!(day.datetime_attr(), "2026-01-28");
+
+        let minutes = date("2026-01-28T09:30");
+        assert_eq!(minutes.time, NaiveTime::from_hms_opt(9, 30, 0));
+        assert_eq!(minutes.offset, None);
+        assert_eq!(minutes.datetime_attr(), "2026-01-28T09:30:00");
+
+        assert_eq!(date("2026-01-28T09:30:15").time, NaiveTime::from_hms_opt(9, 30, 15));
+
+        let zoned = date(" 2026-01-28T09:30:00+09:00 ");
+        assert_eq!(zoned.offset, FixedOffset::east_opt(9 * 3600));
+        assert_eq!(zoned.datetime_attr(), "2026-01-28T09:30:00+09:00");
+    }
+
+    #[test]
+    fn rejects_invalid_dates() {
+        for s in ["", "2026-02-30", "28/01/2026", "2026-01-28 09:30", "2026-01-28T25:00"] {
+            assert_eq!(ArticleDate::parse_str(s), None, "{:?} should be rejected", s);
+        }
+    }
+
+    #[test]
+    fn sorts_offsets_in_utc() {
+        // 09:00 in Tokyo is midnight UTC, before 01:00 without an offset
+        assert!(date("2026-01-28T09:00:00+09:00").sort_key() < date("2026-01-28T01:00").sort_key());
+        assert_eq!(
+            date("2026-01-28T09:00:00+09:00").sort_key(),
+            date("2026-01-28T00:00:00Z").sort_key()
+        );
+        // A date alone sorts at the start of its day
+        assert_eq!(date("2026-01-28").sort_key(), date("2026-01-28T00:00").sort_key());
+        assert!(date("2026-01-27T23:59").sort_key() < date("2026-01-28").sort_key());
+    }
+
+    #[test]
+    fn parses_single_license() {
+        let license = License::parse_str("CC-BY-4.0").unwrap();
+        assert_eq!(license.ids().collect::<Vec<_>>(), [LicenseId::CcBy40]);
+        assert_eq!(license.to_string(), "CC-BY-4.0");
+    }
+
+    #[test]
+    fn parses_license_expressions() {
+        let license = License::parse_str("mit OR Apache-2.0").unwrap();
+        assert_eq!(
+            license.ids().collect::<Vec<_>>(),
+            [LicenseId::Mit, LicenseId::Apache20]
+        );
+        assert_eq!(license.to_string(), "MIT OR Apache-2.0");
+
+        let license = License::parse_str("(MIT OR Apache-2.0) AND CC-BY-4.0").unwrap();
+        assert_eq!(license.to_string(), "(MIT OR Apache-2.0) AND CC-BY-4.0");
+    }
+
+    #[test]
+    fn rejects_invalid_license_expressions() {
+        for expression in ["", "MIT OR", "AND MIT", "MIT Apache-2.0", "(MIT", "MIT)", "()"] {
+            assert!(
+                License::parse_str(expression).is_err(),
+                "{:?} should be rejected",
+                expression
+            );
+        }
+        assert_eq!(
+            License::parse_str("GPL-3.0-only").unwrap_err(),
+            "unknown license GPL-3.0-only (expected one of CC-BY-4.0, CC-BY-SA-4.0, CC0-1.0, MIT, Apache-2.0, MPL-2.0)"
+        );
+        assert_eq!(
+            License::parse_str("Apache-2.0 WITH LLVM-exception").unwrap_err(),
+            "license exceptions (WITH) are not supported"
+        );
+    }
+}
@@ -0,0 +1,318 @@
+use std::collections::HashMap;
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use image::{DynamicImage, ExtendedColorType, ImageEncoder, imageops::FilterType};
+use indexmap::IndexMap;
+use markdown::{mdast::Node, to_mdast};
+use resvg::usvg;
+use sha2::{Digest, Sha256};
+
+use crate::render::markdown_parse_options;
+
+/// Bump when the encoder settings change so cached variants are re-encoded
+const IMAGE_PIPELINE_VERSION: &str = "1";
+const CACHE_DIR: &str = ".cache/images";
+/// Widths generated for `srcset`, in addition to the original width
+const RESPONSIVE_WIDTHS: [u32; 3] = [480, 960, 1440];
+const AVIF_SPEED: u8 = 8;
+const AVIF_QUALITY: u8 = 70;
+
+/// An image copied from an article's asset directory into `dist`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageAsset {
+    /// Site-absolute URL of the copied file (e.g. `/articles/<slug>/cover.png`)
+    pub url: String,
+    pub width: u32,
+    pub height: u32,
+    /// Resized re-encodings, most preferred format first; empty for vector images
+    pub sources: Vec<ImageSource>,
+}
+
+/// One `<source>` of a `<picture>`
+#[derive(Debug, Clone, PartialEq)]
+pub struct ImageSource {
+    pub mime_type: &'static str,
+    /// `srcset` attribute value (e.g. `/articles/<slug>/cover-480w.webp 480w, ...`)
+    pub srcset: String,
+}
+
+/// Images of an article copied or generated into `dist`
+#[derive(Debug, Clone, Default)]
+pub struct ArticleImages {
+    pub cover: Option<ImageAsset>,
+    /// Generated social card, present when the article has no cover
+    pub og_card: Option<ImageAsset>,
+    /// Figure images keyed by the key path of their figure, in document order
+    pub figures: IndexMap<String, ImageAsset>,
+    /// Images referenced from markdown, keyed by their `src`
+    pub inline: HashMap<String, ImageAsset>,
+}
+
+#[derive(Clone, Copy)]
+enum VariantFormat {
+    Avif,
+    WebP,
+}
+
+impl VariantFormat {
+    const ALL: [VariantFormat; 2] = [VariantFormat::Avif, VariantFormat::WebP];
+
+    /// Whether the format is worth generating for a source with this extension.
+    ///
+    /// Known limitation: JPEG sources get no WebP variant. The `image` crate's
+    /// WebP encoder is lossless only, which makes WebP variants of JPEG photos
+    /// larger than the JPEG itself, so browsers without AVIF support get the
+    /// original JPEG. Revisit this if a lossy WebP encoder becomes available.
+    fn suits(self, extension: &str) -> bool {
+        match self {
+            VariantFormat::Avif => true,
+            VariantFormat::WebP => extension == "png",
+        }
+    }
+
+    fn extension(self) -> &'static str {
+        match self {
+            VariantFormat::Avif => "avif",
+            VariantFormat::WebP => "webp",
+        }
+    }
+
+    fn mime_type(self) -> &'static str {
+        match self {
+            VariantFormat::Avif => "image/avif",
+            VariantFormat::WebP => "image/webp",
+        }
+    }
+
+    fn encode(self, image: &DynamicImage) -> Result<Vec<u8>, String> {
+        let rgba = image.to_rgba8();
+        let mut out = Vec::new();
+        let result = match self {
+            VariantFormat::Avif => image::codecs::avif::AvifEncoder::new_with_speed_quality(
+                &mut out,
+                AVIF_SPEED,
+                AVIF_QUALITY,
+            )
+            .write_image(&rgba, rgba.width(), rgba.height(), ExtendedColorType::Rgba8),
+            VariantFormat::WebP => image::codecs::webp::WebPEncoder::new_lossless(&mut out)
+                .write_image(&rgba, rgba.width(), rgba.height(), ExtendedColorType::Rgba8),
+        };
+        result.map_err(|e| format!("Failed to encode {}: {}", self.extension(), e))?;
+        Ok(out)
+    }
+}
+
+/// Directory holding the assets of an article (`articles/<slug>/`)
+pub fn article_asset_dir(slug: &str) -> PathBuf {
+    Path::new("articles").join(slug)
+}
+
+/// Resolve an image relative to the article's asset directory, read its
+/// dimensions and copy it to `dist/articles/<slug>/` together with its
+/// resized AVIF and WebP variants. SVG images are copied as they are, sized
+/// by the `width`/`height` or `viewBox` of their root element.
+pub fn copy_article_image(slug: &str, src: &str) -> Result<ImageAsset, String> {
+    let relative = Path::new(src);
+    if !relative
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err(format!(
+            "{}: asset path must be relative to the article directory: {}",
+            slug, src
+        ));
+    }
+
+    let source_path = article_asset_dir(slug).join(relative);
+    if !source_path.is_file() {
+        return Err(format!("{}: asset not found: {}", slug, source_path.display()));
+    }
+
+    let (width, height) = if is_svg(relative) {
+        svg_size(&source_path)
+    } else {
+        imagesize::size(&source_path)
+            .map(|size| (size.width as u32, size.height as u32))
+            .map_err(|e| e.to_string())
+    }
+    .map_err(|e| {
+        format!(
+            "{}: failed to read image size of {}: {}",
+            slug,
+            source_path.display(),
+            e
+        )
+    })?;
+
+    let dest_path = Path::new("dist/articles").join(slug).join(relative);
+    if let Some(parent) = dest_path.parent() {
+        fs::create_dir_all(parent).map_err(|e| e.to_string())?;
+    }
+    fs::copy(&source_path, &dest_path).map_err(|e| e.to_string())?;
+
+    let sources = if is_raster(relative) {
+        write_variants(slug, src, &source_path, width)
+            .map_err(|e| format!("{}: {}: {}", slug, src, e))?
+    } else {
+        Vec::new()
+    };
+
+    Ok(ImageAsset {
+        url: format!("/articles/{}/{}", slug, src),
+        width,
+        height,
+        sources,
+    })
+}
+
+fn is_svg(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| e.eq_ignore_ascii_case("svg"))
+}
+
+/// Size of an SVG image in CSS pixels
+fn svg_size(path: &Path) -> Result<(u32, u32), String> {
+    let data = fs::read(path).map_err(|e| e.to_string())?;
+    let tree =
+        usvg::Tree::from_data(&data, &usvg::Options::default()).map_err(|e| e.to_string())?;
+    let size = tree.size();
+    Ok((size.width().round() as u32, size.height().round() as u32))
+}
+
+fn is_raster(path: &Path) -> bool {
+    path.extension()
+        .and_then(|e| e.to_str())
+        .is_some_and(|e| matches!(e.to_ascii_lowercase().as_str(), "png" | "jpg" | "jpeg"))
+}
+
+/// Write every width/format variant of an image next to the copied original,
+/// re-encoding only variants missing from the content-hashed cache.
+fn write_variants(
+    slug: &str,
+    src: &str,
+    source_path: &Path,
+    width: u32,
+) -> Result<Vec<ImageSource>, String> {
+    let bytes = fs::read(source_path).map_err(|e| e.to_string())?;
+    let hash = content_hash(&bytes);
+
+    let mut widths: Vec<u32> = RESPONSIVE_WIDTHS
+        .into_iter()
+        .filter(|&w| w < width)
+        .collect();
+    widths.push(width);
+
+    let extension = Path::new(src)
+        .extension()
+        .and_then(|e| e.to_str())
+        .map(|e| e.to_ascii_lowercase())
+        .unwrap_or_default();
+    let mut decoded: Option<DynamicImage> = None;
+    let mut sources = Vec::new();
+
+    for format in VariantFormat::ALL {
+        if !format.suits(&extension) {
+            continue;
+        }
+        let mut srcset = Vec::new();
+        for &w in &widths {
+            let cache_path =
+                Path::new(CACHE_DIR).join(format!("{}-{}.{}", hash, w, format.extension()));
+            if !cache_path.is_file() {
+                if decoded.is_none() {
+                    decoded = Some(
+                        image::load_from_memory(&bytes)
+                            .map_err(|e| format!("failed to decode image: {}", e))?,
+                    );
+                }
+                let image = decoded.as_ref().expect("image decoded above");
+                let resized = if w == width {
+                    image.clone()
+                } else {
+                    image.resize(w, u32::MAX, FilterType::Lanczos3)
+                };
+                fs::create_dir_all(CACHE_DIR).map_err(|e| e.to_string())?;
+                fs::write(&cache_path, format.encode(&resized)?).map_err(|e| e.to_string())?;
+            }
+
+            // Keep the source extension so `cover.png` and `cover.jpg` don't collide
+            let file = format!("{}-{}w.{}", src, w, format.extension());
+            fs::copy(&cache_path, Path::new("dist/articles").join(slug).join(&file))
+                .map_err(|e| e.to_string())?;
+            srcset.push(format!("/articles/{}/{} {}w", slug, file, w));
+        }
+        sources.push(ImageSource {
+            mime_type: format.mime_type(),
+            srcset: srcset.join(", "),
+        });
+    }
+
+    Ok(sources)
+}
+
+fn content_hash(bytes: &[u8]) -> String {
+    let mut hasher = Sha256::new();
+    hasher.update(IMAGE_PIPELINE_VERSION.as_bytes());
+    hasher.update([0]);
+    hasher.update(bytes);
+    hasher
+        .finalize()
+        .iter()
+        .map(|b| format!("{:02x}", b))
+        .collect()
+}
+
+/// Collect the `src` of images in markdown that point into the article's asset directory
+pub fn markdown_image_sources(content: &str) -> Vec<String> {
+    let Ok(root) = to_mdast(content, &markdown_parse_options()) else {
+        return Vec::new();
+    };
+    let mut sources = Vec::new();
+    collect_image_sources(&root, &mut sources);
+    sources
+}
+
+fn collect_image_sources(node: &Node, sources: &mut Vec<String>) {
+    if let Node::Image(image) = node {
+        if is_local(&image.url) {
+            sources.push(image.url.clone());
+        }
+    } else if let Some(children) = node.children() {
+        for child in children {
+            collect_image_sources(child, sources);
+        }
+    }
+}
+
+fn is_local(url: &str) -> bool {
+    !url.starts_with('/') && !url.starts_with('#') && !url.contains(':')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn reads_svg_size_from_root_element() {
+        assert_eq!(svg_size(Path::new("assets/eure-logo.svg")).unwrap(), (412, 422));
+
+        let path = std::env::temp_dir().join(format!("eure-blog-{}.svg", std::process::id()));
+        fs::write(
+            &path,
+            r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 320.4 180"/>"#,
+        )
+        .unwrap();
+        let size = svg_size(&path);
+        fs::remove_file(&path).unwrap();
+        assert_eq!(size.unwrap(), (320, 180));
+    }
+
+    #[test]
+    fn only_png_and_jpeg_get_variants() {
+        assert!(is_raster(Path::new("cover.JPG")));
+        assert!(!is_raster(Path::new("diagram.svg")));
+        assert!(is_svg(Path::new("diagram.SVG")));
+    }
+}
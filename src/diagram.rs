@@ -0,0 +1,93 @@
+use eure::{FromEure, value::Text};
+use indexmap::IndexMap;
+
+/// Diagram described as Eure data and rendered to inline SVG
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub enum Diagram {
+    #[eure(rename = "flowchart")]
+    Flowchart(Flowchart),
+    #[eure(rename = "sequence")]
+    Sequence(Sequence),
+}
+
+/// Boxes connected by arrows, laid out in layers
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Flowchart {
+    /// Direction in which layers are stacked; defaults to `down`
+    #[eure(default)]
+    pub direction: Option<Direction>,
+    /// Node ID to label
+    pub nodes: IndexMap<String, Text>,
+    #[eure(default)]
+    pub edges: Vec<Edge>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, FromEure)]
+pub enum Direction {
+    #[eure(rename = "down")]
+    Down,
+    #[eure(rename = "right")]
+    Right,
+}
+
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Edge {
+    pub from: String,
+    pub to: String,
+    #[eure(default)]
+    pub label: Option<Text>,
+}
+
+/// Participants exchanging messages over time
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Sequence {
+    /// Participant ID to label, in column order
+    pub participants: IndexMap<String, Text>,
+    pub messages: Vec<Message>,
+}
+
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Message {
+    pub from: String,
+    pub to: String,
+    pub label: Text,
+    /// Draw as a dashed return arrow
+    #[eure(default)]
+    pub reply: bool,
+}
+
+impl Diagram {
+    /// Check that every edge and message refers to a declared node or participant
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            Diagram::Flowchart(flowchart) => {
+                if flowchart.nodes.is_empty() {
+                    return Err("Flowchart must have at least one node".to_string());
+                }
+                for edge in &flowchart.edges {
+                    for id in [&edge.from, &edge.to] {
+                        if !flowchart.nodes.contains_key(id) {
+                            return Err(format!("Flowchart edge refers to unknown node: {}", id));
+                        }
+                    }
+                }
+            }
+            Diagram::Sequence(sequence) => {
+                if sequence.participants.is_empty() {
+                    return Err("Sequence diagram must have at least one participant".to_string());
+                }
+                for message in &sequence.messages {
+                    for id in [&message.from, &message.to] {
+                        if !sequence.participants.contains_key(id) {
+                            return Err(format!(
+                                "Sequence message refers to unknown participant: {}",
+                                id
+                            ));
+                        }
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
@@ -0,0 +1,253 @@
+use eure::value::Text;
+use serde_json::{Map, Number, Value};
+
+use crate::article::{Article, CodeGroupBlock, Visit};
+use crate::render::json::eure_to_value;
+
+/// Check that the snippets of every code group marked `$equivalent` hold the
+/// same data. Each snippet is converted to a JSON value according to its
+/// language (Eure, TOML, YAML or JSON) and compared with the first one.
+/// Returns one message per offending group, naming the paths that differ.
+pub fn check_equivalent_code_groups(article: &Article) -> Vec<String> {
+    struct Check(Vec<String>);
+    impl<'a> Visit<'a> for Check {
+        fn visit_code_group(&mut self, path: &str, block: &'a CodeGroupBlock) {
+            if block.equivalent
+                && let Err(e) = check_group(block)
+            {
+                self.0.push(format!("{}.$code-group: {}", path, e));
+            }
+        }
+    }
+    let mut check = Check(Vec::new());
+    article.visit(&mut check);
+    check.0
+}
+
+fn check_group(block: &CodeGroupBlock) -> Result<(), String> {
+    let mut snippets = block.code_group.iter().map(|(label, text)| {
+        snippet_value(text)
+            .map(|value| (label, value))
+            .map_err(|e| format!("{}: {}", label, e))
+    });
+    let Some(first) = snippets.next() else {
+        return Ok(());
+    };
+    let (first_label, first_value) = first?;
+
+    let mut errors = Vec::new();
+    for snippet in snippets {
+        let (label, value) = snippet?;
+        let mut differences = Vec::new();
+        diff(&first_value, &value, "", &mut differences);
+        if !differences.is_empty() {
+            errors.push(format!(
+                "{} differs from {}: {}",
+                label,
+                first_label,
+                differences.join("; ")
+            ));
+        }
+    }
+    if errors.is_empty() {
+        Ok(())
+    } else {
+        Err(errors.join("; "))
+    }
+}
+
+/// Parse a snippet into the JSON value model according to its language
+fn snippet_value(text: &Text) -> Result<Value, String> {
+    let source = text.as_str();
+    match text.language.as_str() {
+        Some("eure") => eure_to_value(source),
+        Some("json") => serde_json::from_str(source).map_err(|e| e.to_string()),
+        Some("toml") => {
+            let table: toml::Table = toml::from_str(source).map_err(|e| e.to_string())?;
+            Ok(toml_to_json(toml::Value::Table(table)))
+        }
+        Some("yaml" | "yml") => {
+            let value: serde_yaml::Value =
+                serde_yaml::from_str(source).map_err(|e| e.to_string())?;
+            yaml_to_json(value)
+        }
+        language => Err(format!(
+            "cannot compare {} snippets",
+            language.unwrap_or("unlabeled")
+        )),
+    }
+}
+
+fn toml_to_json(value: toml::Value) -> Value {
+    match value {
+        toml::Value::String(s) => Value::String(s),
+        toml::Value::Integer(i) => Value::from(i),
+        toml::Value::Float(f) => Number::from_f64(f).map_or(Value::Null, Value::Number),
+        toml::Value::Boolean(b) => Value::Bool(b),
+        toml::Value::Datetime(datetime) => Value::String(datetime.to_string()),
+        toml::Value::Array(values) => Value::Array(values.into_iter().map(toml_to_json).collect()),
+        toml::Value::Table(table) => Value::Object(
+            table
+                .into_iter()
+                .map(|(key, value)| (key, toml_to_json(value)))
+                .collect(),
+        ),
+    }
+}
+
+/// YAML tags become single-key objects, matching how Eure variants convert to JSON
+fn yaml_to_json(value: serde_yaml::Value) -> Result<Value, String> {
+    Ok(match value {
+        serde_yaml::Value::Null => Value::Null,
+        serde_yaml::Value::Bool(b) => Value::Bool(b),
+        serde_yaml::Value::Number(n) => {
+            if let Some(i) = n.as_i64() {
+                Value::from(i)
+            } else if let Some(u) = n.as_u64() {
+                Value::from(u)
+            } else {
+                n.as_f64()
+                    .and_then(Number::from_f64)
+                    .map_or(Value::Null, Value::Number)
+            }
+        }
+        serde_yaml::Value::String(s) => Value::String(s),
+        serde_yaml::Value::Sequence(values) => Value::Array(
+            values
+                .into_iter()
+                .map(yaml_to_json)
+                .collect::<Result<_, _>>()?,
+        ),
+        serde_yaml::Value::Mapping(mapping) => {
+            let mut object = Map::new();
+            for (key, value) in mapping {
+                let serde_yaml::Value::String(key) = key else {
+                    return Err(format!("unsupported mapping key {:?}", key));
+                };
+                object.insert(key, yaml_to_json(value)?);
+            }
+            Value::Object(object)
+        }
+        serde_yaml::Value::Tagged(tagged) => {
+            let tag = tagged.tag.to_string();
+            let mut object = Map::new();
+            object.insert(
+                tag.trim_start_matches('!').to_string(),
+                yaml_to_json(tagged.value)?,
+            );
+            Value::Object(object)
+        }
+    })
+}
+
+/// Record the paths at which `actual` differs from `expected`. Numbers compare
+/// by value, so `1` and `1.0` are equal.
+fn diff(expected: &Value, actual: &Value, path: &str, differences: &mut Vec<String>) {
+    let at = if path.is_empty() { "(root)" } else { path };
+    match (expected, actual) {
+        (Value::Object(expected), Value::Object(actual)) => {
+            let field = |key: &str| {
+                if path.is_empty() {
+                    key.to_string()
+                } else {
+                    format!("{}.{}", path, key)
+                }
+            };
+            for (key, expected) in expected {
+                match actual.get(key) {
+                    Some(actual) => diff(expected, actual, &field(key), differences),
+                    None => differences.push(format!("{} is missing", field(key))),
+                }
+            }
+            for key in actual.keys().filter(|key| !expected.contains_key(*key)) {
+                differences.push(format!("{} is unexpected", field(key)));
+            }
+        }
+        (Value::Array(expected), Value::Array(actual)) => {
+            if expected.len() != actual.len() {
+                differences.push(format!(
+                    "{} has {} items instead of {}",
+                    at,
+                    actual.len(),
+                    expected.len()
+                ));
+                return;
+            }
+            for (i, (expected, actual)) in expected.iter().zip(actual).enumerate() {
+                diff(expected, actual, &format!("{}[{}]", path, i), differences);
+            }
+        }
+        (Value::Number(expected), Value::Number(actual))
+            if expected.as_f64() == actual.as_f64() => {}
+        (expected, actual) if expected == actual => {}
+        (expected, actual) => {
+            differences.push(format!("{} is {} instead of {}", at, actual, expected));
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde_json::json;
+
+    fn differences(expected: Value, actual: Value) -> Vec<String> {
+        let mut differences = Vec::new();
+        diff(&expected, &actual, "", &mut differences);
+        differences
+    }
+
+    #[test]
+    fn equal_values_have_no_differences() {
+        let value = json!({ "a": [1, { "b": "c" }], "d": null });
+        assert!(differences(value.clone(), value).is_empty());
+    }
+
+    #[test]
+    fn numbers_compare_by_value() {
+        assert!(differences(json!({ "n": 1 }), json!({ "n": 1.0 })).is_empty());
+        assert_eq!(
+            differences(json!({ "n": 1 }), json!({ "n": 2 })),
+            ["n is 2 instead of 1"]
+        );
+    }
+
+    #[test]
+    fn reports_nested_paths() {
+        assert_eq!(
+            differences(
+                json!({ "server": { "ports": [80, 443] } }),
+                json!({ "server": { "ports": [80, "443"] } })
+            ),
+            ["server.ports[1] is \"443\" instead of 443"]
+        );
+    }
+
+    #[test]
+    fn reports_missing_and_unexpected_fields() {
+        assert_eq!(
+            differences(json!({ "a": 1, "b": 2 }), json!({ "a": 1, "c": 3 })),
+            ["b is missing", "c is unexpected"]
+        );
+    }
+
+    #[test]
+    fn reports_array_length_once() {
+        assert_eq!(
+            differences(json!({ "list": [1, 2] }), json!({ "list": [1, 3, 4] })),
+            ["list has 3 items instead of 2"]
+        );
+    }
+
+    #[test]
+    fn reports_root_mismatch() {
+        assert_eq!(
+            differences(json!([1]), json!({ "a": 1 })),
+            ["(root) is {\"a\":1} instead of [1]"]
+        );
+        assert_eq!(
+            differences(json!([1, 2]), json!([1])),
+            ["(root) has 1 items instead of 2"]
+        );
+    }
+}
@@ -0,0 +1,346 @@
+use std::collections::HashMap;
+use std::fs;
+use std::ops::Range;
+use std::path::Path;
+
+use eure::document::cst_to_document_and_origin_map;
+use eure::tree::InputSpan;
+use eure_schema::{
+    SchemaDocument,
+    convert::document_to_schema,
+    validate::{ValidationWarning, validate},
+};
+
+use crate::article::{Article, TextBlock, Visit};
+use crate::include::{repo_root, resolve_repo_path};
+
+/// A problem found in an Eure example by the parser or the schema validator
+#[derive(Debug, Clone)]
+pub struct Diagnostic {
+    pub severity: Severity,
+    pub message: String,
+    /// Byte range in the example, when the problem can be located
+    pub span: Option<Range<usize>>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Severity {
+    Error,
+    Warning,
+}
+
+impl Severity {
+    pub fn as_str(self) -> &'static str {
+        match self {
+            Severity::Error => "error",
+            Severity::Warning => "warning",
+        }
+    }
+}
+
+/// Check every Eure code block of an article. Blocks must parse strictly and,
+/// when they name a schema with `$mark.schema`, validate against it. Blocks
+/// marked with `expect-invalid` must instead fail to parse, or fail
+/// validation when they name a schema, and blocks marked with
+/// `show-diagnostics` must produce at least one diagnostic. Blocks listing
+/// `expect-diagnostics` must produce exactly those diagnostics. Returns one
+/// message per offending block, prefixed with its section key path.
+pub fn check_eure_examples(article: &Article) -> Vec<String> {
+    let blocks = eure_blocks(article);
+
+    let mut errors = Vec::new();
+    for block in &blocks {
+        let mark = block.mark.cloned().unwrap_or_default();
+        let diagnostics = match diagnose_block(block, &blocks) {
+            Ok(diagnostics) => diagnostics,
+            Err(e) => {
+                errors.push(format!("{}: {}", block.path, e));
+                continue;
+            }
+        };
+        let failures: Vec<String> = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.severity == Severity::Error)
+            .map(|diagnostic| describe(block.text.as_str(), diagnostic))
+            .collect();
+
+        if !mark.expect_diagnostics.is_empty() {
+            if let Err(e) =
+                match_expected(block.text.as_str(), &diagnostics, &mark.expect_diagnostics)
+            {
+                errors.push(format!("{}: {}", block.path, e));
+            }
+        } else if mark.show_diagnostics {
+            if diagnostics.is_empty() {
+                errors.push(format!(
+                    "{}: marked show-diagnostics but produces no diagnostics",
+                    block.path
+                ));
+            }
+        } else if mark.expect_invalid {
+            if failures.is_empty() {
+                errors.push(format!(
+                    "{}: marked expect-invalid but {}",
+                    block.path,
+                    if mark.schema.is_some() {
+                        "validates against its schema"
+                    } else {
+                        "parses successfully"
+                    }
+                ));
+            }
+        } else if !failures.is_empty() {
+            errors.push(format!("{}: {}", block.path, failures.join("; ")));
+        }
+    }
+    errors
+}
+
+/// Diagnostics of the blocks marked with `show-diagnostics`, keyed by block path.
+/// The article must have passed `check_eure_examples`.
+pub fn collect_diagnostics(article: &Article) -> HashMap<String, Vec<Diagnostic>> {
+    let blocks = eure_blocks(article);
+    blocks
+        .iter()
+        .filter(|block| block.mark.is_some_and(|mark| mark.show_diagnostics))
+        .map(|block| {
+            let diagnostics =
+                diagnose_block(block, &blocks).expect("Eure examples are checked before rendering");
+            (block.path.clone(), diagnostics)
+        })
+        .collect()
+}
+
+fn eure_blocks(article: &Article) -> Vec<TextBlock<'_>> {
+    struct Collect<'a>(Vec<TextBlock<'a>>);
+    impl<'a> Visit<'a> for Collect<'a> {
+        fn visit_text(&mut self, block: TextBlock<'a>) {
+            if block.text.language.is_other("eure") {
+                self.0.push(block);
+            }
+        }
+    }
+    let mut collect = Collect(Vec::new());
+    article.visit(&mut collect);
+    collect.0
+}
+
+/// Run the parser, build the document so that errors such as duplicate keys
+/// are caught, and, when the block names a schema, run the schema validator.
+fn diagnose_block(block: &TextBlock, blocks: &[TextBlock]) -> Result<Vec<Diagnostic>, String> {
+    let mark = block.mark.cloned().unwrap_or_default();
+    let schema = match &mark.schema {
+        Some(name) => {
+            let source = resolve_schema(name, block, blocks)?;
+            Some(parse_schema(&source).map_err(|e| format!("invalid schema {}: {}", name, e))?)
+        }
+        None => None,
+    };
+    Ok(diagnose(block.text.as_str(), schema.as_ref()))
+}
+
+fn diagnose(source: &str, schema: Option<&SchemaDocument>) -> Vec<Diagnostic> {
+    let cst = match eure::parol::parse(source) {
+        Ok(cst) => cst,
+        Err(e) => return parse_diagnostics(&e),
+    };
+    let (doc, origins) = match cst_to_document_and_origin_map(source, &cst) {
+        Ok(parsed) => parsed,
+        Err(e) => {
+            return vec![Diagnostic {
+                severity: Severity::Error,
+                message: e.error.to_string(),
+                span: e
+                    .error
+                    .span_with_origin_map(&cst, &e.partial_origins)
+                    .map(range),
+            }];
+        }
+    };
+    let Some(schema) = schema else {
+        return Vec::new();
+    };
+
+    let output = validate(&doc, schema);
+    let errors = output.errors.iter().map(|error| {
+        let (node_id, _) = error.node_ids();
+        Diagnostic {
+            severity: Severity::Error,
+            message: error.to_string(),
+            span: origins
+                .get_value_span(node_id, &cst)
+                .or_else(|| origins.get_definition_span(node_id, &cst))
+                .map(range),
+        }
+    });
+    let warnings = output.warnings.iter().map(|warning| Diagnostic {
+        severity: Severity::Warning,
+        message: match warning {
+            ValidationWarning::UnknownExtension { name, path } => {
+                format!("Unknown extension '${}' at path {}", name, path)
+            }
+            ValidationWarning::DeprecatedField { field, path } => {
+                format!("Deprecated field '{}' at path {}", field, path)
+            }
+        },
+        span: None,
+    });
+    errors.chain(warnings).collect()
+}
+
+fn parse_diagnostics(error: &eure::parol::EureParseError) -> Vec<Diagnostic> {
+    error
+        .entries
+        .iter()
+        .map(|entry| Diagnostic {
+            severity: Severity::Error,
+            message: entry.message.lines().next().unwrap_or_default().to_string(),
+            span: entry.span.map(range),
+        })
+        .collect()
+}
+
+/// Read the schema named by `$mark.schema`: a sibling Eure block of the same
+/// section, a section key path within the article, or a file in the repository
+fn resolve_schema(name: &str, block: &TextBlock, blocks: &[TextBlock]) -> Result<String, String> {
+    let parent = block.path.rsplit_once('.').map_or("", |(parent, _)| parent);
+    let sibling = if parent.is_empty() {
+        name.to_string()
+    } else {
+        format!("{}.{}", parent, name)
+    };
+    if let Some(schema) = [sibling.as_str(), name]
+        .into_iter()
+        .find_map(|path| blocks.iter().find(|b| b.path == path))
+    {
+        return Ok(schema.text.as_str().to_string());
+    }
+    if Path::new(name).is_file() {
+        return resolve_repo_path(&repo_root()?, name)
+            .and_then(|path| fs::read_to_string(path).map_err(|e| e.to_string()))
+            .map_err(|e| format!("schema {}: {}", name, e));
+    }
+    Err(format!(
+        "schema {} is neither an Eure block of the article nor a file",
+        name
+    ))
+}
+
+fn parse_schema(source: &str) -> Result<SchemaDocument, String> {
+    let cst = eure::parol::parse(source).map_err(|e| e.to_string())?;
+    let (doc, _) = cst_to_document_and_origin_map(source, &cst).map_err(|e| e.to_string())?;
+    let (schema, _) = document_to_schema(&doc).map_err(|e| e.to_string())?;
+    Ok(schema)
+}
+
+/// Compare diagnostics with the entries of `expect-diagnostics`. An entry
+/// matches a diagnostic by its description, or by its message alone when the
+/// entry names no line.
+fn match_expected(
+    source: &str,
+    diagnostics: &[Diagnostic],
+    expected: &[String],
+) -> Result<(), String> {
+    let mut missing: Vec<&str> = expected.iter().map(String::as_str).collect();
+    let mut unexpected = Vec::new();
+    for diagnostic in diagnostics {
+        let description = describe(source, diagnostic);
+        match missing
+            .iter()
+            .position(|&entry| entry == description || entry == diagnostic.message)
+        {
+            Some(i) => {
+                missing.remove(i);
+            }
+            None => unexpected.push(description),
+        }
+    }
+    let mut problems = Vec::new();
+    if !missing.is_empty() {
+        problems.push(format!(
+            "missing expected diagnostics: {}",
+            missing.join("; ")
+        ));
+    }
+    if !unexpected.is_empty() {
+        problems.push(format!("unexpected diagnostics: {}", unexpected.join("; ")));
+    }
+    if problems.is_empty() {
+        Ok(())
+    } else {
+        Err(problems.join(", "))
+    }
+}
+
+/// Describe a diagnostic for the build log, with the line it starts on
+fn describe(source: &str, diagnostic: &Diagnostic) -> String {
+    match &diagnostic.span {
+        Some(span) => {
+            let start = span.start.min(source.len());
+            let line = source.as_bytes()[..start]
+                .iter()
+                .filter(|&&b| b == b'\n')
+                .count()
+                + 1;
+            format!("{} (line {})", diagnostic.message, line)
+        }
+        None => diagnostic.message.clone(),
+    }
+}
+
+fn range(span: InputSpan) -> Range<usize> {
+    span.start as usize..span.end as usize
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn check(schema: &str) -> Vec<String> {
+        let article = crate::parse_article(&format!(
+            "$frontmatter {{\n  title: Title\n  date = `2026-01-01`\n}}\n\n'#': Title\n\nexample = ```eure\nname: Eure\n```\nexample.$mark.schema = \"{}\"\n",
+            schema
+        ))
+        .unwrap();
+        check_eure_examples(&article)
+    }
+
+    #[test]
+    fn reads_schema_files_of_the_repository() {
+        let errors = check("series.schema.eure");
+        assert!(
+            errors.iter().all(|e| !e.starts_with("example: schema")),
+            "{:?}",
+            errors
+        );
+    }
+
+    #[test]
+    fn rejects_schema_files_outside_the_repository() {
+        let outside =
+            std::env::temp_dir().join(format!("eure-blog-{}.schema.eure", std::process::id()));
+        fs::write(&outside, "name = `text`\n").unwrap();
+        let absolute = outside.display().to_string();
+        let errors = check(&absolute);
+        fs::remove_file(&outside).unwrap();
+        assert_eq!(
+            errors,
+            [format!(
+                "example: schema {}: path must be relative to the repository root",
+                absolute
+            )]
+        );
+
+        // Leaving the repository is rejected even when the path leads back into it
+        let root = repo_root().unwrap();
+        let name = root.file_name().unwrap().to_str().unwrap();
+        let relative = format!("../{}/series.schema.eure", name);
+        assert_eq!(
+            check(&relative),
+            [format!(
+                "example: schema {}: path must be relative to the repository root",
+                relative
+            )]
+        );
+    }
+}
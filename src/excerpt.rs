@@ -0,0 +1,236 @@
+use eure::value::{Language, Text};
+use markdown::{mdast::Node, to_mdast};
+
+use crate::article::Article;
+use crate::footnote::strip_refs;
+use crate::render::markdown_parse_options;
+
+/// Maximum length of a generated excerpt, in characters
+pub const EXCERPT_MAX_CHARS: usize = 280;
+
+/// Marker in a markdown block that ends the excerpt
+pub const MORE_MARKER: &str = "<!-- more -->";
+
+/// Generate the excerpt of an article.
+///
+/// A block marked with `$mark.excerpt` wins, then all the prose before a
+/// `<!-- more -->` marker, then the first prose block truncated at a
+/// sentence boundary.
+pub fn article_excerpt(article: &Article) -> Option<String> {
+    let blocks = article.text_blocks();
+
+    if let Some(block) = blocks
+        .iter()
+        .find(|block| block.mark.is_some_and(|mark| mark.excerpt))
+    {
+        return Some(to_plain_text(block.text)).filter(|s| !s.is_empty());
+    }
+
+    let prose: Vec<&Text> = blocks
+        .iter()
+        .filter(|block| !block.mark.is_some_and(|mark| mark.dangerously_inner_html))
+        .map(|block| block.text)
+        .filter(|text| is_prose(text))
+        .collect();
+
+    if let Some(marker) = prose
+        .iter()
+        .position(|text| text.as_str().contains(MORE_MARKER))
+    {
+        let before = prose[marker]
+            .as_str()
+            .split_once(MORE_MARKER)
+            .map(|(before, _)| before)
+            .unwrap_or_default();
+        let excerpt = prose[..marker]
+            .iter()
+            .map(|text| to_plain_text(text))
+            .chain([markdown_to_plain_text(before)])
+            .filter(|s| !s.is_empty())
+            .collect::<Vec<_>>()
+            .join(" ");
+        return Some(excerpt).filter(|s| !s.is_empty());
+    }
+
+    prose
+        .iter()
+        .map(|text| to_plain_text(text))
+        .find(|s| !s.is_empty())
+        .map(|s| truncate_at_sentence(&s, EXCERPT_MAX_CHARS))
+}
+
+/// Description for meta tags: the frontmatter description, falling back to the excerpt.
+///
+/// Errors when the article has neither, so that no page ships without a description.
+pub fn meta_description(article: &Article) -> Result<String, String> {
+    article
+        .frontmatter
+        .description
+        .as_ref()
+        .map(|description| description.as_str().to_string())
+        .or_else(|| article_excerpt(article))
+        .ok_or_else(|| {
+            "article has no description: set `description` in the frontmatter or start with a prose block for the excerpt"
+                .to_string()
+        })
+}
+
+fn is_prose(text: &Text) -> bool {
+    matches!(text.language, Language::Plaintext) || text.language.is_other("markdown")
+}
+
+fn to_plain_text(text: &Text) -> String {
+    let content = text.as_str();
+    if text.language.is_other("markdown") {
+        markdown_to_plain_text(content)
+    } else {
+        collapse_whitespace(&strip_refs(content))
+    }
+}
+
+/// Strip markdown syntax and footnote references, keeping the readable text of
+/// paragraphs, lists and headings
+pub fn markdown_to_plain_text(content: &str) -> String {
+    let Ok(root) = to_mdast(content, &markdown_parse_options()) else {
+        return collapse_whitespace(content);
+    };
+    let mut out = String::new();
+    collect_plain_text(&root, &mut out);
+    collapse_whitespace(&out)
+}
+
+fn collect_plain_text(node: &Node, out: &mut String) {
+    match node {
+        Node::Text(text) => out.push_str(&strip_refs(&text.value)),
+        Node::InlineCode(code) => out.push_str(&code.value),
+        Node::Break(_) => out.push(' '),
+        Node::Code(_) | Node::Math(_) | Node::InlineMath(_) | Node::Html(_) | Node::Image(_) => {}
+        Node::Definition(_) | Node::FootnoteDefinition(_) | Node::Table(_) => {}
+        _ => {
+            if let Some(children) = node.children() {
+                for child in children {
+                    collect_plain_text(child, out);
+                }
+            }
+            if is_block(node) {
+                out.push(' ');
+            }
+        }
+    }
+}
+
+fn is_block(node: &Node) -> bool {
+    matches!(
+        node,
+        Node::Paragraph(_) | Node::Heading(_) | Node::ListItem(_) | Node::Blockquote(_)
+    )
+}
+
+fn collapse_whitespace(s: &str) -> String {
+    s.split_whitespace().collect::<Vec<_>>().join(" ")
+}
+
+/// Truncate to at most `max_chars`, preferring to end at a sentence boundary
+fn truncate_at_sentence(s: &str, max_chars: usize) -> String {
+    if s.chars().count() <= max_chars {
+        return s.to_string();
+    }
+    let limit = s
+        .char_indices()
+        .nth(max_chars)
+        .map(|(i, _)| i)
+        .unwrap_or(s.len());
+    let head = &s[..limit];
+
+    let sentence_end = head
+        .char_indices()
+        .filter(|&(i, c)| {
+            matches!(c, '.' | '!' | '?') && s[i + c.len_utf8()..].starts_with(char::is_whitespace)
+        })
+        .map(|(i, c)| i + c.len_utf8())
+        .next_back();
+    if let Some(end) = sentence_end {
+        return head[..end].to_string();
+    }
+
+    let word_end = head.rfind(char::is_whitespace).unwrap_or(head.len());
+    format!("{}…", head[..word_end].trim_end())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn article(body: &str) -> Article {
+        crate::parse_article(&format!(
+            "$frontmatter {{\n  title: Title\n  date = `2026-01-01`\n}}\n\n'#': Title\n\n{}",
+            body
+        ))
+        .unwrap()
+    }
+
+    #[test]
+    fn truncate_keeps_short_strings() {
+        assert_eq!(truncate_at_sentence("Short. Text", 11), "Short. Text");
+        assert_eq!(truncate_at_sentence("", 0), "");
+    }
+
+    #[test]
+    fn truncate_ends_at_last_sentence() {
+        assert_eq!(
+            truncate_at_sentence("One. Two! Three? Four five six", 20),
+            "One. Two! Three?"
+        );
+    }
+
+    #[test]
+    fn truncate_ignores_periods_inside_words() {
+        assert_eq!(
+            truncate_at_sentence("Version 1.2 is out and more words follow", 24),
+            "Version 1.2 is out and…"
+        );
+    }
+
+    #[test]
+    fn truncate_falls_back_to_word_boundary() {
+        assert_eq!(
+            truncate_at_sentence("no sentence end in this text", 12),
+            "no sentence…"
+        );
+        assert_eq!(truncate_at_sentence("unbroken", 4), "unbr…");
+    }
+
+    #[test]
+    fn truncate_counts_characters_not_bytes() {
+        assert_eq!(
+            truncate_at_sentence("ユーアは良い。とても良い", 8),
+            "ユーアは良い。と…"
+        );
+    }
+
+    #[test]
+    fn description_falls_back_to_excerpt() {
+        let article = article("intro: First sentence. Second sentence.\n");
+        assert_eq!(
+            meta_description(&article).unwrap(),
+            "First sentence. Second sentence."
+        );
+    }
+
+    #[test]
+    fn more_marker_keeps_preceding_blocks() {
+        let article = article(
+            "first: First block.\nsecond = ```markdown\nSecond *block*.\n\n<!-- more -->\n\nRest.\n```\n",
+        );
+        assert_eq!(
+            article_excerpt(&article).unwrap(),
+            "First block. Second block."
+        );
+    }
+
+    #[test]
+    fn missing_description_and_excerpt_is_an_error() {
+        let article = article("");
+        assert!(meta_description(&article).is_err());
+    }
+}
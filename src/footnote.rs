@@ -0,0 +1,191 @@
+use std::collections::{HashMap, HashSet};
+
+use eure::value::{Language, Text};
+use markdown::{mdast::Node, to_mdast};
+
+use crate::article::{Article, TextBlock, Visit};
+use crate::render::markdown_parse_options;
+
+/// A footnote body with its number
+#[derive(Debug, Clone)]
+pub struct Footnote<'a> {
+    pub key: &'a str,
+    /// 1-based, in order of first reference
+    pub number: usize,
+    pub body: &'a Text,
+}
+
+/// Footnotes of an article, ordered by number
+#[derive(Debug, Clone, Default)]
+pub struct Footnotes<'a> {
+    pub notes: Vec<Footnote<'a>>,
+}
+
+impl<'a> Footnotes<'a> {
+    pub fn get(&self, key: &str) -> Option<&Footnote<'a>> {
+        self.notes.iter().find(|note| note.key == key)
+    }
+}
+
+/// Collect the footnotes of an article, numbering them in order of first
+/// reference. Fails on undefined, unused or duplicate footnotes.
+pub fn collect_footnotes(article: &Article) -> Result<Footnotes<'_>, String> {
+    #[derive(Default)]
+    struct Collect<'a> {
+        bodies: HashMap<&'a str, &'a Text>,
+        duplicates: Vec<&'a str>,
+        refs: Vec<String>,
+    }
+    impl<'a> Visit<'a> for Collect<'a> {
+        fn visit_text(&mut self, block: TextBlock<'a>) {
+            self.refs.extend(text_refs(block.text));
+        }
+        fn visit_footnote(&mut self, key: &'a str, body: &'a Text) {
+            if self.bodies.insert(key, body).is_some() {
+                self.duplicates.push(key);
+            }
+        }
+    }
+
+    let mut collect = Collect::default();
+    article.visit(&mut collect);
+
+    if let Some(key) = collect.duplicates.first() {
+        return Err(format!("Duplicate footnote: {}", key));
+    }
+
+    let mut footnotes = Footnotes::default();
+    let mut seen = HashSet::new();
+    for key in &collect.refs {
+        if !seen.insert(key.as_str()) {
+            continue;
+        }
+        let Some((&key, &body)) = collect.bodies.get_key_value(key.as_str()) else {
+            return Err(format!("Footnote reference to undefined footnote: [^{}]", key));
+        };
+        footnotes.notes.push(Footnote {
+            key,
+            number: footnotes.notes.len() + 1,
+            body,
+        });
+    }
+
+    let mut unused: Vec<&str> = collect
+        .bodies
+        .keys()
+        .copied()
+        .filter(|key| !seen.contains(key))
+        .collect();
+    unused.sort();
+    if let Some(key) = unused.first() {
+        return Err(format!("Unused footnote: {}", key));
+    }
+
+    Ok(footnotes)
+}
+
+/// Keys of the `[^key]` references in a text, outside of code
+fn text_refs(text: &Text) -> Vec<String> {
+    if matches!(text.language, Language::Plaintext) {
+        return find_refs(text.as_str())
+            .map(|(_, _, key)| key.to_string())
+            .collect();
+    }
+    if !text.language.is_other("markdown") {
+        return Vec::new();
+    }
+    let Ok(root) = to_mdast(text.as_str(), &markdown_parse_options()) else {
+        return Vec::new();
+    };
+    let mut refs = Vec::new();
+    collect_markdown_refs(&root, &mut refs);
+    refs
+}
+
+fn collect_markdown_refs(node: &Node, refs: &mut Vec<String>) {
+    match node {
+        Node::Text(text) => {
+            refs.extend(find_refs(&text.value).map(|(_, _, key)| key.to_string()));
+        }
+        Node::Code(_) | Node::InlineCode(_) | Node::Html(_) => {}
+        Node::Math(_) | Node::InlineMath(_) => {}
+        _ => {
+            if let Some(children) = node.children() {
+                for child in children {
+                    collect_markdown_refs(child, refs);
+                }
+            }
+        }
+    }
+}
+
+/// Find `[^key]` references, yielding `(start, end, key)` byte ranges
+pub fn find_refs(s: &str) -> impl Iterator<Item = (usize, usize, &str)> {
+    let mut offset = 0;
+    std::iter::from_fn(move || {
+        while let Some(found) = s[offset..].find("[^") {
+            let start = offset + found;
+            let key_start = start + 2;
+            let key_len = s[key_start..]
+                .find(|c: char| !(c.is_ascii_alphanumeric() || c == '-' || c == '_'))
+                .unwrap_or(s.len() - key_start);
+            let key_end = key_start + key_len;
+            if key_len > 0 && s[key_end..].starts_with(']') {
+                offset = key_end + 1;
+                return Some((start, key_end + 1, &s[key_start..key_end]));
+            }
+            offset = key_start;
+        }
+        None
+    })
+}
+
+/// Remove `[^key]` references from plain text
+pub fn strip_refs(s: &str) -> String {
+    let mut out = String::with_capacity(s.len());
+    let mut last = 0;
+    for (start, end, _) in find_refs(s) {
+        out.push_str(&s[last..start]);
+        last = end;
+    }
+    out.push_str(&s[last..]);
+    out
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn keys(s: &str) -> Vec<&str> {
+        find_refs(s).map(|(_, _, key)| key).collect()
+    }
+
+    #[test]
+    fn finds_refs_with_byte_ranges() {
+        let s = "Eure[^eure] and TOML[^toml-1_a].";
+        let refs: Vec<_> = find_refs(s).collect();
+        assert_eq!(refs, [(4, 11, "eure"), (20, 31, "toml-1_a")]);
+        assert_eq!(&s[4..11], "[^eure]");
+    }
+
+    #[test]
+    fn skips_malformed_refs() {
+        assert_eq!(keys("[^] [^a b] [^unclosed [^ok]"), ["ok"]);
+        assert_eq!(keys("[^[^nested]]"), ["nested"]);
+        assert_eq!(keys("trailing [^"), Vec::<&str>::new());
+        assert_eq!(keys("[link](url) [x]"), Vec::<&str>::new());
+    }
+
+    #[test]
+    fn finds_refs_after_multibyte_text() {
+        let s = "ユーア[^note]";
+        assert_eq!(find_refs(s).collect::<Vec<_>>(), [(9, 16, "note")]);
+    }
+
+    #[test]
+    fn strips_refs() {
+        assert_eq!(strip_refs("Eure[^a] is[^b-2] nice[^c]."), "Eure is nice.");
+        assert_eq!(strip_refs("no refs [^ here"), "no refs [^ here");
+        assert_eq!(strip_refs("[^only]"), "");
+    }
+}
@@ -0,0 +1,317 @@
+use std::fs;
+use std::path::{Component, Path, PathBuf};
+
+use eure::value::{Language, Text};
+
+use crate::article::{Article, MarkOptions, VisitMut};
+
+/// Replace the content of blocks marked with `$mark.include` by the file they
+/// name. The directive is `path`, `path#L10`, `path#L10-L30` or `path#region`,
+/// where a region spans the lines between `ANCHOR: region` and
+/// `ANCHOR_END: region` markers. A marker is a line comment (`//`, `#`, `--`,
+/// `;`, `/* */` or `<!-- -->`) holding nothing but the marker. Paths are
+/// relative to the repository root and may not leave it, also not through
+/// symlinks. An included region is dedented and the markers of nested
+/// regions are dropped from it; whole files and line ranges are included
+/// verbatim. The block takes the language of the file.
+pub fn resolve_includes(article: &mut Article) -> Result<(), String> {
+    let root = repo_root()?;
+    article.visit_mut(&mut Includes { root })
+}
+
+/// Canonical path of the repository root, the directory the site is built from
+pub fn repo_root() -> Result<PathBuf, String> {
+    std::env::current_dir()
+        .and_then(|dir| dir.canonicalize())
+        .map_err(|e| format!("cannot resolve the repository root: {}", e))
+}
+
+/// Resolve a path relative to the canonical repository `root`, rejecting
+/// paths that leave it, also through symlinks
+pub fn resolve_repo_path(root: &Path, file: &str) -> Result<PathBuf, String> {
+    if !Path::new(file)
+        .components()
+        .all(|c| matches!(c, Component::Normal(_)))
+    {
+        return Err("path must be relative to the repository root".to_string());
+    }
+    let path = root.join(file).canonicalize().map_err(|e| e.to_string())?;
+    if !path.starts_with(root) {
+        return Err("path resolves outside of the repository root".to_string());
+    }
+    Ok(path)
+}
+
+struct Includes {
+    /// Canonical path of the repository root
+    root: PathBuf,
+}
+
+impl VisitMut for Includes {
+    type Error = String;
+
+    fn visit_text(
+        &mut self,
+        path: &str,
+        text: &mut Text,
+        mark: Option<&mut MarkOptions>,
+    ) -> Result<(), String> {
+        let Some(directive) = mark.and_then(|mark| mark.include.as_ref()) else {
+            return Ok(());
+        };
+        let content = read_include(&self.root, directive)
+            .map_err(|e| format!("{}: include {}: {}", path, directive, e))?;
+        let (file, _) = directive.split_once('#').unwrap_or((directive, ""));
+        let language = match Path::new(file).extension().and_then(|ext| ext.to_str()) {
+            Some(extension) => Language::new(language_of(extension).to_string()),
+            None => text.language.clone(),
+        };
+        *text = Text::new(content, language);
+        Ok(())
+    }
+}
+
+fn read_include(root: &Path, directive: &str) -> Result<String, String> {
+    let (file, selector) = match directive.split_once('#') {
+        Some((file, selector)) => (file, Some(selector)),
+        None => (directive, None),
+    };
+    let path = resolve_repo_path(root, file)?;
+    let source = fs::read_to_string(&path).map_err(|e| e.to_string())?;
+    let lines: Vec<&str> = source.lines().collect();
+
+    match selector {
+        None => Ok(join_lines(&lines)),
+        Some(selector) => match parse_line_range(selector) {
+            Some((start, end)) => {
+                if start == 0 || start > end || end > lines.len() {
+                    return Err(format!(
+                        "line range {} is outside of the file's {} lines",
+                        selector,
+                        lines.len()
+                    ));
+                }
+                Ok(join_lines(&lines[start - 1..end]))
+            }
+            None => {
+                let selected: Vec<&str> = region(&lines, selector)?
+                    .into_iter()
+                    .filter(|line| anchor_marker(line).is_none())
+                    .collect();
+                Ok(dedent(&selected))
+            }
+        },
+    }
+}
+
+/// Parse `L10` or `L10-L30` into an inclusive, 1-based line range
+fn parse_line_range(selector: &str) -> Option<(usize, usize)> {
+    let (start, end) = selector.split_once('-').unwrap_or((selector, selector));
+    let line = |s: &str| s.strip_prefix('L')?.parse().ok();
+    Some((line(start)?, line(end)?))
+}
+
+#[derive(Debug, PartialEq)]
+enum Anchor<'a> {
+    Start(&'a str),
+    End(&'a str),
+}
+
+/// Parse a region marker line such as `// ANCHOR: name` or
+/// `<!-- ANCHOR_END: name -->`. Lines that merely mention a marker are not markers.
+fn anchor_marker(line: &str) -> Option<Anchor<'_>> {
+    let line = line.trim();
+    let (body, close) = ["<!--", "/*", "//", "--", "#", ";"]
+        .into_iter()
+        .find_map(|open| {
+            let body = line.strip_prefix(open)?;
+            let close = match open {
+                "<!--" => "-->",
+                "/*" => "*/",
+                _ => "",
+            };
+            Some((body, close))
+        })?;
+    let body = body.trim_start_matches(['/', '#', ';']).trim();
+    let body = body.strip_suffix(close)?.trim_end();
+    let is_name = |name: &str| {
+        !name.is_empty()
+            && name
+                .chars()
+                .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+    };
+    if let Some(name) = body.strip_prefix("ANCHOR:").map(str::trim) {
+        is_name(name).then_some(Anchor::Start(name))
+    } else if let Some(name) = body.strip_prefix("ANCHOR_END:").map(str::trim) {
+        is_name(name).then_some(Anchor::End(name))
+    } else {
+        None
+    }
+}
+
+/// Lines between the `ANCHOR: name` and `ANCHOR_END: name` markers
+fn region<'a>(lines: &[&'a str], name: &str) -> Result<Vec<&'a str>, String> {
+    let start = lines
+        .iter()
+        .position(|line| anchor_marker(line) == Some(Anchor::Start(name)))
+        .ok_or_else(|| format!("region {} not found", name))?;
+    let end = lines[start..]
+        .iter()
+        .position(|line| anchor_marker(line) == Some(Anchor::End(name)))
+        .ok_or_else(|| format!("region {} is not closed by ANCHOR_END: {}", name, name))?;
+    Ok(lines[start + 1..start + end].to_vec())
+}
+
+/// Remove the indentation shared by all non-blank lines
+fn dedent(lines: &[&str]) -> String {
+    let indent = lines
+        .iter()
+        .filter(|line| !line.trim().is_empty())
+        .map(|line| line.len() - line.trim_start().len())
+        .min()
+        .unwrap_or(0);
+    let dedented: Vec<&str> = lines
+        .iter()
+        .map(|line| line.get(indent..).unwrap_or(""))
+        .collect();
+    join_lines(&dedented)
+}
+
+fn join_lines(lines: &[&str]) -> String {
+    lines.iter().map(|line| format!("{}\n", line)).collect()
+}
+
+/// Language name understood by the highlighters for a file extension
+fn language_of(extension: &str) -> &str {
+    match extension {
+        "rs" => "rust",
+        "md" => "markdown",
+        "yml" => "yaml",
+        "py" => "python",
+        "js" => "javascript",
+        "ts" => "typescript",
+        "sh" => "bash",
+        other => other,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn parses_line_ranges() {
+        assert_eq!(parse_line_range("L10"), Some((10, 10)));
+        assert_eq!(parse_line_range("L10-L30"), Some((10, 30)));
+        assert_eq!(parse_line_range("10-30"), None);
+        assert_eq!(parse_line_range("L10-"), None);
+        assert_eq!(parse_line_range("region"), None);
+    }
+
+    #[test]
+    fn parses_anchor_markers() {
+        assert_eq!(
+            anchor_marker("// ANCHOR: main"),
+            Some(Anchor::Start("main"))
+        );
+        assert_eq!(
+            anchor_marker("    # ANCHOR_END: main"),
+            Some(Anchor::End("main"))
+        );
+        assert_eq!(
+            anchor_marker("-- ANCHOR: a-b_1"),
+            Some(Anchor::Start("a-b_1"))
+        );
+        assert_eq!(
+            anchor_marker(";; ANCHOR: lisp"),
+            Some(Anchor::Start("lisp"))
+        );
+        assert_eq!(
+            anchor_marker("/* ANCHOR: css */"),
+            Some(Anchor::Start("css"))
+        );
+        assert_eq!(
+            anchor_marker("<!-- ANCHOR_END: html -->"),
+            Some(Anchor::End("html"))
+        );
+    }
+
+    #[test]
+    fn ignores_lines_mentioning_anchors() {
+        assert_eq!(anchor_marker(r#"let s = "ANCHOR: main";"#), None);
+        assert_eq!(anchor_marker("// see the ANCHOR: main marker"), None);
+        assert_eq!(anchor_marker("// ANCHOR: two words"), None);
+        assert_eq!(anchor_marker("// ANCHOR:"), None);
+        assert_eq!(anchor_marker("<!-- ANCHOR: unclosed"), None);
+    }
+
+    #[test]
+    fn selects_region_without_nested_markers() {
+        let lines = [
+            "fn main() {",
+            "    // ANCHOR: body",
+            "    // ANCHOR: inner",
+            "    let s = \"ANCHOR: not a marker\";",
+            "    // ANCHOR_END: inner",
+            "    // ANCHOR_END: body",
+            "}",
+        ];
+        let selected: Vec<&str> = region(&lines, "body")
+            .unwrap()
+            .into_iter()
+            .filter(|line| anchor_marker(line).is_none())
+            .collect();
+        assert_eq!(selected, ["    let s = \"ANCHOR: not a marker\";"]);
+        assert_eq!(region(&lines, "inner").unwrap().len(), 1);
+    }
+
+    #[test]
+    fn reports_missing_regions() {
+        let lines = ["// ANCHOR: open", "code"];
+        assert_eq!(
+            region(&lines, "other").unwrap_err(),
+            "region other not found"
+        );
+        assert_eq!(
+            region(&lines, "open").unwrap_err(),
+            "region open is not closed by ANCHOR_END: open"
+        );
+    }
+
+    #[test]
+    fn reads_files_inside_the_root_only() {
+        let dir = std::env::temp_dir().join(format!("eure-blog-include-{}", std::process::id()));
+        let root = dir.join("repo");
+        let _ = fs::remove_dir_all(&dir);
+        fs::create_dir_all(&root).unwrap();
+        fs::write(dir.join("secret.txt"), "secret\n").unwrap();
+        fs::write(
+            root.join("code.rs"),
+            "a\n    // ANCHOR: inner\n    b\n    c\n    // ANCHOR_END: inner\nd\n",
+        )
+        .unwrap();
+        let root = root.canonicalize().unwrap();
+
+        assert_eq!(
+            read_include(&root, "code.rs#L3-L4").unwrap(),
+            "    b\n    c\n"
+        );
+        assert_eq!(read_include(&root, "code.rs#inner").unwrap(), "b\nc\n");
+        assert_eq!(
+            read_include(&root, "code.rs").unwrap(),
+            "a\n    // ANCHOR: inner\n    b\n    c\n    // ANCHOR_END: inner\nd\n"
+        );
+        assert!(read_include(&root, "code.rs#L6-L7").is_err());
+        assert!(read_include(&root, "../secret.txt").is_err());
+        #[cfg(unix)]
+        {
+            std::os::unix::fs::symlink(dir.join("secret.txt"), root.join("link.txt")).unwrap();
+            assert_eq!(
+                read_include(&root, "link.txt").unwrap_err(),
+                "path resolves outside of the repository root"
+            );
+        }
+
+        fs::remove_dir_all(&dir).unwrap();
+    }
+}
@@ -1,18 +1,42 @@
 mod article;
+mod asset;
+mod diagram;
+mod equivalence;
+mod examples;
+mod excerpt;
+mod footnote;
+mod include;
+mod partial;
 mod render;
+mod series;
+mod site;
 mod templates;
 
+use std::collections::{HashMap, hash_map::Entry};
 use std::fs;
 use std::process::Command;
 
 use article::Article;
-use render::{CodeHighlighter, eure_highlight::generate_eure_css};
-use templates::{index::ArticleEntry, render_article_page, render_index_page, render_source_page};
+use asset::{ArticleImages, copy_article_image, markdown_image_sources};
+use equivalence::check_equivalent_code_groups;
+use examples::check_eure_examples;
+use eure::FromEure;
+use include::resolve_includes;
+use indexmap::IndexMap;
+use partial::expand_partials;
+use render::{CodeHighlighter, eure_highlight::generate_eure_css, og_card::OgCardRenderer};
+use series::{Series, SeriesPosition, collect_series};
+use site::Site;
+use templates::{
+    index::ArticleEntry, render_article_page, render_index_page, render_series_page,
+    render_source_page,
+};
 
 fn main() -> Result<(), Box<dyn std::error::Error>> {
     // 1. Create directories
     fs::create_dir_all("dist/articles")?;
     fs::create_dir_all("dist/source")?;
+    fs::create_dir_all("dist/series")?;
     fs::create_dir_all("dist/styles")?;
 
     // 2. Copy favicon assets
@@ -31,7 +55,8 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
     let syntax_css = highlighter.generate_css()?;
     fs::write("dist/styles/syntax.css", syntax_css)?;
     fs::write("dist/styles/eure-syntax.css", generate_eure_css())?;
-    fs::write("dist/styles/main.css", generate_main_css())?;
+    let site = read_site_config()?;
+    fs::write("dist/styles/main.css", generate_main_css(&site))?;
 
     // 6. Read and parse articles (slug, source_content, article)
     let mut articles: Vec<(String, String, Article)> = Vec::new();
@@ -41,12 +66,19 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         if path.extension().is_some_and(|e| e == "eure") {
             let content = fs::read_to_string(&path)?;
             match parse_article(&content) {
-                Ok(article) => {
+                Ok(mut article) => {
                     // Skip drafts
                     if article.frontmatter.draft {
                         println!("Skipping draft: {:?}", path);
                         continue;
                     }
+                    if let Err(e) = expand_partials(&mut article)
+                        .and_then(|()| resolve_includes(&mut article))
+                        .and_then(|()| site.expand_article(&mut article))
+                    {
+                        parse_errors.push((path.clone(), e));
+                        continue;
+                    }
                     let slug = path.file_stem().unwrap().to_string_lossy().to_string();
                     articles.push((slug, content, article));
                 }
@@ -66,13 +98,97 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         return Err("Article parse errors".into());
     }
 
-    // Sort by slug (descending for newest first)
-    articles.sort_by(|a, b| b.0.cmp(&a.0));
+    // Fail build if an Eure example doesn't parse or an equivalent code group disagrees
+    let mut example_errors: Vec<(&str, String)> = Vec::new();
+    for (slug, _, article) in &articles {
+        for error in check_eure_examples(article)
+            .into_iter()
+            .chain(check_equivalent_code_groups(article))
+        {
+            example_errors.push((slug, error));
+        }
+    }
+    if !example_errors.is_empty() {
+        eprintln!(
+            "\nBuild failed with {} invalid Eure example(s):",
+            example_errors.len()
+        );
+        for (slug, err) in &example_errors {
+            eprintln!("  {}: {}", slug, err);
+        }
+        return Err("Invalid Eure examples".into());
+    }
+
+    // Sort by date (newest first), using the slug as tiebreaker
+    articles.sort_by(|a, b| {
+        let a_date = a.2.frontmatter.date.map(|date| date.sort_key());
+        let b_date = b.2.frontmatter.date.map(|date| date.sort_key());
+        b_date.cmp(&a_date).then_with(|| b.0.cmp(&a.0))
+    });
+
+    // 7. Group articles into series
+    let series_catalog = read_series_catalog()?;
+    let series_entries = collect_series(
+        &series_catalog,
+        articles
+            .iter()
+            .map(|(slug, _, article)| (slug.as_str(), article)),
+    )?;
+
+    // 8. Copy article images, generating social cards for articles without a cover
+    let og_card_renderer = OgCardRenderer::new()?;
+    let mut images: HashMap<&str, ArticleImages> = HashMap::new();
+    for (slug, _, article) in &articles {
+        let mut article_images = ArticleImages::default();
+        if let Some(cover) = &article.frontmatter.cover {
+            if cover.alt.as_str().trim().is_empty() {
+                return Err(format!("{}: cover image must have alt text", slug).into());
+            }
+            article_images.cover = Some(copy_article_image(slug, &cover.src)?);
+        } else {
+            let og_card = og_card_renderer.generate(slug, article)?;
+            println!("Generated: dist{}", og_card.url);
+            article_images.og_card = Some(og_card);
+        }
+        for (path, figure) in article.figures() {
+            if figure.alt.as_str().trim().is_empty() {
+                return Err(format!("{}: figure {} must have alt text", slug, figure.src).into());
+            }
+            let image = copy_article_image(slug, &figure.src)?;
+            article_images.figures.insert(path, image);
+        }
+        for block in article.text_blocks() {
+            if !block.text.language.is_other("markdown") {
+                continue;
+            }
+            for src in markdown_image_sources(block.text.as_str()) {
+                if let Entry::Vacant(entry) = article_images.inline.entry(src) {
+                    let image = copy_article_image(slug, entry.key())?;
+                    entry.insert(image);
+                }
+            }
+        }
+        images.insert(slug, article_images);
+    }
 
-    // 7. Generate article pages and source pages
+    // 9. Generate article pages and source pages
     for (slug, source_content, article) in &articles {
+        let series_position = series_entries.iter().find_map(|entry| {
+            entry
+                .position_of(slug)
+                .map(|index| SeriesPosition { entry, index })
+        });
+
         // Generate article page
-        match render_article_page(article, slug, commit_hash.as_deref(), &highlighter) {
+        match render_article_page(
+            article,
+            slug,
+            series_position,
+            &images[slug.as_str()],
+            commit_hash.as_deref(),
+            &highlighter,
+            &site,
+        ) {
             Ok(html) => {
                 let path = format!("dist/articles/{}.html", slug);
                 fs::write(&path, html.into_string())?;
@@ -97,12 +213,20 @@ fn main() -> Result<(), Box<dyn std::error::Error>> {
         println!("Generated: {}", source_path);
     }
 
-    // 8. Generate index page
+    // 10. Generate series overview pages
+    for entry in &series_entries {
+        let path = format!("dist/series/{}.html", entry.id);
+        fs::write(&path, render_series_page(entry).into_string())?;
+        println!("Generated: {}", path);
+    }
+
+    // 11. Generate index page
     let entries: Vec<ArticleEntry> = articles
         .iter()
         .map(|(slug, _, article)| ArticleEntry {
             slug: slug.as_str(),
             article,
+            cover: images[slug.as_str()].cover.as_ref(),
         })
         .collect();
     let index_html = render_index_page(&entries);
@@ -129,13 +253,39 @@ fn get_git_commit_hash() -> Option<String> {
 }
 
 fn parse_article(input: &str) -> Result<Article, String> {
+    parse_eure(input, "Article")
+}
+
+/// Read series metadata from `series.eure`, if present
+fn read_series_catalog() -> Result<IndexMap<String, Series>, Box<dyn std::error::Error>> {
+    let path = "series.eure";
+    if !std::path::Path::new(path).exists() {
+        return Ok(IndexMap::new());
+    }
+    let content = fs::read_to_string(path)?;
+    let catalog = parse_eure(&content, "Series").map_err(|e| format!("{}: {}", path, e))?;
+    Ok(catalog)
+}
+
+/// Read site-wide settings from `site.eure`
+fn read_site_config() -> Result<Site, Box<dyn std::error::Error>> {
+    let path = "site.eure";
+    let content = fs::read_to_string(path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut site: Site = parse_eure(&content, "Site").map_err(|e| format!("{}: {}", path, e))?;
+    site.load_alerts().map_err(|e| format!("{}: {}", path, e))?;
+    Ok(site)
+}
+
+fn parse_eure<T>(input: &str, kind: &str) -> Result<T, String>
+where
+    T: for<'doc> FromEure<'doc>,
+    for<'doc> <T as FromEure<'doc>>::Error: std::fmt::Debug,
+{
     let cst = eure::parol::parse(input).map_err(|e| format!("Parse error: {:?}", e))?;
     let doc = eure::document::cst_to_document(input, &cst)
         .map_err(|e| format!("Document error: {:?}", e))?;
-    let article: Article = doc
-        .parse(doc.get_root_id())
-        .map_err(|e| format!("Article parse error: {:?}", e))?;
-    Ok(article)
+    doc.parse(doc.get_root_id())
+        .map_err(|e| format!("{} parse error: {:?}", kind, e))
 }
 
 fn copy_favicon_assets() -> Result<(), Box<dyn std::error::Error>> {
@@ -187,8 +337,8 @@ fn copy_favicon_assets() -> Result<(), Box<dyn std::error::Error>> {
     Ok(())
 }
 
-fn generate_main_css() -> String {
-    r#"/* Main Styles - Catppuccin Mocha Theme */
+fn generate_main_css(site: &Site) -> String {
+    let mut css = r#"/* Main Styles - Catppuccin Mocha Theme */
 :root {
     --ctp-base: #1e1e2e;
     --ctp-mantle: #181825;
@@ -384,6 +534,11 @@ a:hover {
     font-size: 0.875rem;
 }
 
+.article-excerpt {
+    color: var(--ctp-subtext0);
+    margin-top: 0.25rem;
+}
+
 .article-tags {
     display: flex;
     gap: 0.5rem;
@@ -485,6 +640,20 @@ h4.section-header { font-size: 1.125rem; }
 h5.section-header { font-size: 1rem; }
 h6.section-header { font-size: 0.875rem; }
 
+.header-anchor {
+    margin-left: 0.5rem;
+    color: var(--ctp-overlay1);
+    text-decoration: none;
+    opacity: 0;
+    transition: opacity 0.2s;
+}
+
+.section-header:hover .header-anchor,
+.markdown-content :is(h1, h2, h3, h4, h5, h6):hover .header-anchor,
+.header-anchor:focus {
+    opacity: 1;
+}
+
 .content-item,
 .content-list-item {
     margin: 1rem 0;
@@ -570,6 +739,20 @@ h6.section-header { font-size: 0.875rem; }
     padding: 0;
 }
 
+/* Highlighted fences look like standalone code blocks */
+.markdown-content pre[data-language] {
+    line-height: 1.5;
+}
+
+.markdown-content pre.giallo {
+    background-color: var(--ctp-base);
+}
+
+.markdown-content pre[data-language] code {
+    color: inherit;
+    font-size: inherit;
+}
+
 .markdown-content blockquote {
     border-left: 3px solid var(--ctp-mauve);
     padding-left: 1rem;
@@ -683,6 +866,28 @@ h6.section-header { font-size: 0.875rem; }
     color: var(--ctp-red);
 }
 
+/* Details Blocks */
+.content-details {
+    margin: 1rem 0;
+    border: 1px solid var(--ctp-surface1);
+    border-radius: 8px;
+}
+
+.content-details-summary {
+    cursor: pointer;
+    padding: 0.75rem 1rem;
+    font-weight: 600;
+    color: var(--ctp-text);
+}
+
+.content-details-summary:hover {
+    background-color: var(--ctp-surface0);
+}
+
+.content-details-body {
+    padding: 0 1rem;
+}
+
 /* Giallo Code Blocks */
 .giallo {
     padding: 1rem;
@@ -769,6 +974,423 @@ pre[data-language]::before {
     margin-top: 1rem;
 }
 
+/* Eure Diagnostics */
+.diagnostic-annotation {
+    font-style: italic;
+}
+
+.diagnostic-annotation.diagnostic-error,
+.diagnostic.diagnostic-error {
+    color: var(--ctp-red);
+}
+
+.diagnostic-annotation.diagnostic-warning,
+.diagnostic.diagnostic-warning {
+    color: var(--ctp-yellow);
+}
+
+.diagnostic-marker {
+    font-style: normal;
+    font-weight: bold;
+}
+
+.diagnostic {
+    margin: -0.5rem 0 1rem;
+    padding: 0.5rem 1rem;
+    background-color: var(--ctp-mantle);
+    border-left: 3px solid currentColor;
+    border-radius: 0 0.25rem 0.25rem 0;
+    font-family: 'JetBrains Mono', 'Fira Code', monospace;
+    font-size: 0.875rem;
+}
+
+/* JSON Views */
+.json-view {
+    display: grid;
+    grid-template-columns: minmax(0, 1fr);
+    gap: 0 1rem;
+}
+
+@media (min-width: 900px) {
+    .json-view {
+        grid-template-columns: repeat(2, minmax(0, 1fr));
+    }
+}
+
+/* Code Groups */
+.code-group {
+    display: flex;
+    flex-wrap: wrap;
+    margin: 1rem 0;
+}
+
+.code-group-input {
+    position: absolute;
+    opacity: 0;
+    pointer-events: none;
+}
+
+.code-group-tab {
+    order: 1;
+    padding: 0.375rem 1rem;
+    color: var(--ctp-subtext0);
+    font-size: 0.875rem;
+    border: 1px solid transparent;
+    border-bottom: none;
+    border-radius: 0.5rem 0.5rem 0 0;
+    cursor: pointer;
+    user-select: none;
+}
+
+.code-group-tab:hover {
+    color: var(--ctp-text);
+}
+
+.code-group-input:checked + .code-group-tab {
+    color: var(--ctp-mauve);
+    background-color: var(--ctp-mantle);
+    border-color: var(--ctp-surface1);
+}
+
+.code-group-input:focus-visible + .code-group-tab {
+    outline: 2px solid var(--ctp-mauve);
+    outline-offset: -2px;
+}
+
+.code-group-panel {
+    order: 2;
+    display: none;
+    width: 100%;
+}
+
+.code-group-input:checked + .code-group-tab + .code-group-panel {
+    display: block;
+}
+
+.code-group-panel pre {
+    margin: 0;
+    border-top-left-radius: 0;
+}
+
+.code-group-panel pre[data-language] {
+    padding-top: 1rem;
+}
+
+.code-group-panel pre[data-language]::before {
+    content: none;
+}
+
+/* Diagrams */
+.diagram-container {
+    margin: 1.5rem 0;
+    overflow-x: auto;
+}
+
+.diagram {
+    display: block;
+    max-width: 100%;
+    height: auto;
+    margin: 0 auto;
+    font-family: inherit;
+}
+
+.diagram text {
+    fill: var(--ctp-text);
+    font-size: 14px;
+}
+
+.diagram-node rect {
+    fill: var(--ctp-surface0);
+    stroke: var(--ctp-mauve);
+    stroke-width: 1.5;
+}
+
+.diagram-edge path {
+    fill: none;
+    stroke: var(--ctp-overlay1);
+    stroke-width: 1.5;
+}
+
+.diagram-reply path {
+    stroke-dasharray: 6 4;
+}
+
+.diagram-arrowhead {
+    fill: var(--ctp-overlay1);
+}
+
+.diagram-label rect {
+    fill: var(--ctp-base);
+}
+
+.diagram .diagram-label text,
+.diagram .diagram-message-label {
+    fill: var(--ctp-subtext0);
+    font-size: 12px;
+}
+
+.diagram-lifeline {
+    stroke: var(--ctp-surface2);
+    stroke-dasharray: 4 4;
+}
+
+/* Math */
+.math-display {
+    margin: 1rem 0;
+    overflow-x: auto;
+}
+
+.math-display math {
+    font-size: 1.15em;
+}
+
+/* Responsive Images */
+picture {
+    display: contents;
+}
+
+.markdown-content img {
+    max-width: 100%;
+    height: auto;
+}
+
+/* Cover Images */
+.article-cover {
+    display: block;
+    width: 100%;
+    height: auto;
+    margin-top: 1.5rem;
+    border-radius: 8px;
+}
+
+.article-thumbnail-link {
+    display: block;
+    margin-bottom: 0.75rem;
+}
+
+.article-thumbnail {
+    display: block;
+    width: 100%;
+    max-height: 240px;
+    height: auto;
+    object-fit: cover;
+    border-radius: 8px;
+}
+
+/* Figures */
+.article-figure {
+    margin: 1.5rem 0;
+}
+
+.article-figure img {
+    display: block;
+    max-width: 100%;
+    height: auto;
+    margin: 0 auto;
+    border-radius: 8px;
+}
+
+.article-figure figcaption {
+    margin-top: 0.5rem;
+    color: var(--ctp-subtext0);
+    font-size: 0.875rem;
+    text-align: center;
+}
+
+.figure-number {
+    font-weight: 600;
+    color: var(--ctp-text);
+}
+
+.figure-caption,
+.figure-caption .markdown-content p {
+    display: inline;
+    margin: 0;
+}
+
+.figure-number + .figure-caption::before {
+    content: ": ";
+}
+
+.figure-credit {
+    margin-top: 0.25rem;
+    color: var(--ctp-overlay1);
+    font-size: 0.8rem;
+}
+
+.figure-credit .markdown-content,
+.figure-credit .markdown-content p {
+    display: inline;
+    margin: 0;
+}
+
+/* Footnotes */
+.footnote-ref {
+    font-size: 0.75em;
+    line-height: 0;
+}
+
+.footnote-ref a {
+    text-decoration: none;
+}
+
+.sidenote {
+    display: none;
+}
+
+.footnotes {
+    margin-top: 3rem;
+    padding-top: 1rem;
+    border-top: 1px solid var(--ctp-surface0);
+    color: var(--ctp-subtext1);
+    font-size: 0.9rem;
+}
+
+.footnotes li {
+    margin-bottom: 0.5rem;
+}
+
+.footnotes .markdown-content,
+.footnotes .markdown-content p {
+    display: inline;
+    margin: 0;
+}
+
+.footnote-backref {
+    margin-left: 0.25rem;
+    text-decoration: none;
+}
+
+@media (min-width: 1440px) {
+    .sidenote {
+        display: block;
+        float: right;
+        clear: right;
+        width: 220px;
+        margin-right: -250px;
+        margin-top: 0.25rem;
+        color: var(--ctp-subtext0);
+        font-size: 0.8rem;
+        font-style: normal;
+        font-weight: normal;
+        line-height: 1.4;
+    }
+
+    .sidenote-number {
+        font-weight: 600;
+        color: var(--ctp-mauve);
+    }
+
+    .footnotes {
+        display: none;
+    }
+}
+
+/* Article Footer */
+.article-footer {
+    margin-top: 3rem;
+    padding-top: 1rem;
+    border-top: 1px solid var(--ctp-surface0);
+    color: var(--ctp-overlay1);
+    font-size: 0.875rem;
+}
+
+/* Series */
+.series-box {
+    margin-bottom: 2rem;
+    padding: 0.75rem 1rem;
+    border: 1px solid var(--ctp-surface1);
+    border-left: 4px solid var(--ctp-lavender);
+    border-radius: 8px;
+    background-color: var(--ctp-mantle);
+}
+
+.series-box-title {
+    font-weight: 600;
+    margin-bottom: 0.5rem;
+}
+
+.series-parts {
+    padding-left: 1.5rem;
+}
+
+.series-part {
+    margin: 0.25rem 0;
+}
+
+.series-part-current {
+    color: var(--ctp-lavender);
+    font-weight: 600;
+}
+
+.series-nav {
+    display: flex;
+    justify-content: space-between;
+    gap: 1rem;
+    margin-top: 3rem;
+    padding-top: 1rem;
+    border-top: 1px solid var(--ctp-surface0);
+}
+
+.series-nav a {
+    display: flex;
+    flex-direction: column;
+    max-width: 48%;
+}
+
+.series-nav-next {
+    margin-left: auto;
+    text-align: right;
+}
+
+.series-nav-label {
+    color: var(--ctp-overlay1);
+    font-size: 0.875rem;
+}
+
+.series-nav-title {
+    color: var(--ctp-text);
+}
+
+/* Series Page */
+.series-page {
+    padding: 1rem 0;
+}
+
+.series-header {
+    margin-bottom: 2rem;
+}
+
+.series-kicker {
+    color: var(--ctp-overlay1);
+    font-size: 0.875rem;
+    text-transform: uppercase;
+    letter-spacing: 0.05em;
+}
+
+.series-header .page-title {
+    margin-bottom: 0.5rem;
+}
+
+.series-description {
+    color: var(--ctp-subtext1);
+}
+
+.series-article-list {
+    list-style: none;
+}
+
+.series-part-number {
+    display: block;
+    color: var(--ctp-lavender);
+    font-size: 0.875rem;
+}
+
+.article-description {
+    color: var(--ctp-subtext0);
+    margin-top: 0.25rem;
+}
+
 /* Responsive */
 @media (max-width: 768px) {
     .site-main {
@@ -780,5 +1402,50 @@ pre[data-language]::before {
     }
 }
 "#
-    .to_string()
+    .to_string();
+    css.push_str(&site.alert_css());
+    css
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Article exercising the authoring features, built by the tests but
+    /// never published, so that feature demos stay out of published copy
+    const FIXTURE: &str = "fixtures/features.eure";
+
+    #[test]
+    fn fixture_article_builds() {
+        let content = fs::read_to_string(FIXTURE).unwrap();
+        let mut article = parse_article(&content).unwrap();
+        expand_partials(&mut article).unwrap();
+        resolve_includes(&mut article).unwrap();
+        let site = read_site_config().unwrap();
+        site.expand_article(&mut article).unwrap();
+
+        let errors: Vec<String> = check_eure_examples(&article)
+            .into_iter()
+            .chain(check_equivalent_code_groups(&article))
+            .collect();
+        assert_eq!(errors, Vec::<String>::new());
+
+        let highlighter = CodeHighlighter::new().unwrap();
+        let html = render_article_page(
+            &article,
+            "features",
+            None,
+            &ArticleImages::default(),
+            None,
+            &highlighter,
+            &site,
+        )
+        .unwrap()
+        .into_string();
+        assert!(html.contains(r#"class="code-group""#));
+        assert!(html.contains(r#"class="json-view""#));
+        assert!(html.contains("diagnostic-error"));
+        assert!(html.contains(r#"data-partial="try-eure""#));
+        assert!(html.contains("alert alert-experimental"));
+    }
 }
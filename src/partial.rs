@@ -0,0 +1,152 @@
+use std::fs;
+
+use eure::{FromEure, value::Text};
+use indexmap::IndexMap;
+
+use crate::article::{
+    Article, Item, Level2, Level3, Level4, Level5, Level6, PartialBlock, TextOrNested,
+};
+use crate::parse_eure;
+use crate::site::is_variable_name;
+
+/// Replace every `$partial` section of the article with the sections of
+/// `partials/<name>.eure`, parsed at the level the partial is used at.
+/// Partials may use other partials; a partial that ends up including itself
+/// is an error, as is an override whose key the partial doesn't define, and
+/// so is a name of anything but ASCII letters, digits, `-` and `_`.
+///
+/// Sections of an expanded partial keep their keys as IDs, so a partial that
+/// contains nested sections or markdown headings can only be used once per
+/// article; a second use fails the build with a duplicate section ID.
+/// `article.schema.eure` describes partial files for editors along with articles.
+pub fn expand_partials(article: &mut Article) -> Result<(), String> {
+    expand_sections(&mut article.sections, &mut Vec::new())
+}
+
+fn expand_sections<T: Expand>(
+    sections: &mut IndexMap<String, Item<T>>,
+    stack: &mut Vec<String>,
+) -> Result<(), String> {
+    for (key, item) in sections {
+        match item {
+            Item::Normal(value) => value.expand(stack)?,
+            Item::List(values) => {
+                for value in values {
+                    value.expand(stack)?;
+                }
+            }
+            Item::Partial(block) => {
+                expand_block(block, stack).map_err(|e| format!("{}: {}", key, e))?
+            }
+            Item::Toc(_)
+            | Item::Figure(_)
+            | Item::Footnote(_)
+            | Item::Diagram(_)
+            | Item::CodeGroup(_) => {}
+        }
+    }
+    Ok(())
+}
+
+fn expand_block<T: Expand>(
+    block: &mut PartialBlock<T>,
+    stack: &mut Vec<String>,
+) -> Result<(), String> {
+    let name = &block.partial;
+    if !is_variable_name(name) {
+        return Err(format!("invalid partial name: {:?}", name));
+    }
+    if stack.contains(name) {
+        return Err(format!("partial cycle: {} -> {}", stack.join(" -> "), name));
+    }
+    let path = format!("partials/{}.eure", name);
+    let content = fs::read_to_string(&path).map_err(|e| format!("{}: {}", path, e))?;
+    let mut sections: IndexMap<String, Item<T>> =
+        parse_eure(&content, "Partial").map_err(|e| format!("{}: {}", path, e))?;
+
+    stack.push(name.clone());
+    expand_sections(&mut sections, stack)?;
+    stack.pop();
+
+    expand_sections(&mut block.sections, stack)?;
+    for (key, item) in block.sections.drain(..) {
+        let Some(section) = sections.get_mut(&key) else {
+            return Err(format!(
+                "override {} is not a section of partial {}",
+                key, name
+            ));
+        };
+        *section = item;
+    }
+    block.sections = sections;
+    Ok(())
+}
+
+/// Trait for expanding the partials of nested sections
+trait Expand: for<'doc> FromEure<'doc, Error = eure::document::parse::ParseError> {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String>;
+}
+
+impl Expand for Text {
+    fn expand(&mut self, _stack: &mut Vec<String>) -> Result<(), String> {
+        Ok(())
+    }
+}
+
+impl<T: Expand> Expand for TextOrNested<T> {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String> {
+        match self {
+            TextOrNested::Text { .. } => Ok(()),
+            TextOrNested::Nested(nested) => nested.expand(stack),
+        }
+    }
+}
+
+impl Expand for Level2 {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String> {
+        expand_sections(&mut self.sections, stack)
+    }
+}
+
+impl Expand for Level3 {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String> {
+        expand_sections(&mut self.sections, stack)
+    }
+}
+
+impl Expand for Level4 {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String> {
+        expand_sections(&mut self.sections, stack)
+    }
+}
+
+impl Expand for Level5 {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String> {
+        expand_sections(&mut self.sections, stack)
+    }
+}
+
+impl Expand for Level6 {
+    fn expand(&mut self, stack: &mut Vec<String>) -> Result<(), String> {
+        expand_sections(&mut self.sections, stack)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn rejects_partial_names_that_leave_the_partials_directory() {
+        for name in ["../articles/000-alpha-release", "/etc/passwd", "a/b", ""] {
+            let mut block = PartialBlock::<Level2> {
+                partial: name.to_string(),
+                sections: IndexMap::new(),
+            };
+            assert_eq!(
+                expand_block(&mut block, &mut Vec::new()).unwrap_err(),
+                format!("invalid partial name: {:?}", name)
+            );
+        }
+    }
+}
@@ -18,7 +18,17 @@ impl CodeHighlighter {
     }
 
     pub fn highlight(&self, code: &str, language: &str) -> Markup {
-        let options = HighlightOptions::new(language, ThemeVariant::Single("catppuccin-mocha"));
+        self.highlight_with_fallback(code, language, false)
+    }
+
+    /// Like `highlight`, but renders code of an unknown language as plain text
+    pub fn highlight_or_plain(&self, code: &str, language: &str) -> Markup {
+        self.highlight_with_fallback(code, language, true)
+    }
+
+    fn highlight_with_fallback(&self, code: &str, language: &str, fallback: bool) -> Markup {
+        let options = HighlightOptions::new(language, ThemeVariant::Single("catppuccin-mocha"))
+            .fallback_to_plain(fallback);
 
         let html_output = match self.registry.highlight(code, &options) {
             Ok(highlighted) => {
@@ -39,8 +49,8 @@ impl CodeHighlighter {
         // Add data-language attribute to the <pre> tag
         let display_lang = format_language_name(language);
         let html_with_lang = html_output.replacen(
-            "<pre class=\"giallo hl-code\">",
-            &format!("<pre class=\"giallo hl-code\" data-language=\"{}\">", display_lang),
+            "<pre class=\"giallo hl-code\"",
+            &format!("<pre class=\"giallo hl-code\" data-language=\"{}\"", display_lang),
             1,
         );
 
@@ -0,0 +1,72 @@
+use maud::{Markup, PreEscaped, html};
+
+use crate::examples::Diagnostic;
+use crate::render::eure_highlight::highlight_eure_lines;
+
+/// Diagnostic pointing into a line, underlined from `column` for `width` characters
+struct Annotation<'a> {
+    column: usize,
+    width: usize,
+    diagnostic: &'a Diagnostic,
+}
+
+/// Render an Eure example with its diagnostics annotated under the lines they
+/// point at, marking each span with carets. Diagnostics without a span are
+/// listed after the code.
+pub fn render_diagnostics(source: &str, diagnostics: &[Diagnostic]) -> Markup {
+    let mut offset = 0;
+    let rows: Vec<(String, Vec<Annotation>)> = source
+        .split('\n')
+        .zip(highlight_eure_lines(source))
+        .map(|(text, highlighted)| {
+            let (start, end) = (offset, offset + text.len());
+            offset = end + 1;
+            let annotations = diagnostics
+                .iter()
+                .filter_map(|diagnostic| {
+                    let span = diagnostic.span.as_ref()?;
+                    // A span starting at the line break points just past the line's end
+                    if !(start..=end).contains(&span.start) {
+                        return None;
+                    }
+                    let column = text.get(..span.start - start)?.chars().count();
+                    let width = text
+                        .get(span.start - start..span.end.clamp(span.start, end) - start)
+                        .map_or(0, |underlined| underlined.chars().count());
+                    Some(Annotation {
+                        column,
+                        width: width.max(1),
+                        diagnostic,
+                    })
+                })
+                .collect();
+            (highlighted, annotations)
+        })
+        .collect();
+
+    html! {
+        div.eure-diagnostics {
+            pre.code-block.eure-diagnostics-source data-language="Eure" {
+                code {
+                    @for (i, (highlighted, annotations)) in rows.iter().enumerate() {
+                        @if i > 0 { "\n" }
+                        (PreEscaped(highlighted))
+                        @for annotation in annotations {
+                            "\n"
+                            span class=(format!("diagnostic-annotation diagnostic-{}", annotation.diagnostic.severity.as_str())) {
+                                (" ".repeat(annotation.column))
+                                span.diagnostic-marker { ("^".repeat(annotation.width)) }
+                                " " (annotation.diagnostic.severity.as_str()) ": " (annotation.diagnostic.message)
+                            }
+                        }
+                    }
+                }
+            }
+            @for diagnostic in diagnostics.iter().filter(|diagnostic| diagnostic.span.is_none()) {
+                div class=(format!("diagnostic diagnostic-{}", diagnostic.severity.as_str())) {
+                    (diagnostic.severity.as_str()) ": " (diagnostic.message)
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,593 @@
+use maud::{html, Markup};
+
+use crate::diagram::{Diagram, Direction, Flowchart, Sequence};
+
+const MARGIN: f32 = 16.0;
+const FONT_SIZE: f32 = 14.0;
+const LABEL_FONT_SIZE: f32 = 12.0;
+const NODE_HEIGHT: f32 = 36.0;
+const NODE_MIN_WIDTH: f32 = 80.0;
+const NODE_PADDING: f32 = 24.0;
+/// Gap between nodes of the same layer
+const NODE_GAP: f32 = 32.0;
+/// Gap between layers, leaving room for edge labels
+const LAYER_GAP: f32 = 64.0;
+/// Spacing of the lanes that route edges around the nodes
+const LANE_GAP: f32 = 20.0;
+const LANE_RADIUS: f32 = 8.0;
+const PARTICIPANT_GAP: f32 = 40.0;
+const MESSAGE_GAP: f32 = 44.0;
+const SELF_MESSAGE_WIDTH: f32 = 32.0;
+const ARROW_LENGTH: f32 = 8.0;
+const ARROW_HALF_WIDTH: f32 = 4.5;
+
+/// Render a diagram to inline SVG. The diagram must have been validated.
+pub fn render_diagram(diagram: &Diagram) -> Markup {
+    let svg = match diagram {
+        Diagram::Flowchart(flowchart) => render_flowchart(flowchart),
+        Diagram::Sequence(sequence) => render_sequence(sequence),
+    };
+    html! { div.diagram-container { (svg) } }
+}
+
+/// Approximate rendered width of a label; the SVG is drawn with page fonts,
+/// so real glyph metrics aren't available at build time.
+fn text_width(text: &str, font_size: f32) -> f32 {
+    text.chars()
+        .map(|c| if c.is_ascii() { 0.6 } else { 1.0 })
+        .sum::<f32>()
+        * font_size
+}
+
+#[derive(Clone, Copy)]
+struct Point {
+    x: f32,
+    y: f32,
+}
+
+/// Arrowhead triangle ending at `tip`, pointing away from `from`
+fn arrowhead(from: Point, tip: Point) -> String {
+    let (dx, dy) = (tip.x - from.x, tip.y - from.y);
+    let len = (dx * dx + dy * dy).sqrt().max(f32::EPSILON);
+    let (ux, uy) = (dx / len, dy / len);
+    let base = Point {
+        x: tip.x - ux * ARROW_LENGTH,
+        y: tip.y - uy * ARROW_LENGTH,
+    };
+    format!(
+        "{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+        tip.x,
+        tip.y,
+        base.x - uy * ARROW_HALF_WIDTH,
+        base.y + ux * ARROW_HALF_WIDTH,
+        base.x + uy * ARROW_HALF_WIDTH,
+        base.y - ux * ARROW_HALF_WIDTH
+    )
+}
+
+fn edge_label(text: &str, at: Point) -> Markup {
+    let width = text_width(text, LABEL_FONT_SIZE) + 8.0;
+    let height = LABEL_FONT_SIZE + 6.0;
+    html! {
+        g.diagram-label {
+            rect x=(at.x - width / 2.0) y=(at.y - height / 2.0) width=(width) height=(height) rx="3" {}
+            text x=(at.x) y=(at.y) text-anchor="middle" dominant-baseline="central" { (text) }
+        }
+    }
+}
+
+struct NodeBox {
+    x: f32,
+    y: f32,
+    width: f32,
+}
+
+impl NodeBox {
+    fn center(&self) -> Point {
+        Point {
+            x: self.x + self.width / 2.0,
+            y: self.y + NODE_HEIGHT / 2.0,
+        }
+    }
+}
+
+fn render_flowchart(flowchart: &Flowchart) -> Markup {
+    let direction = flowchart.direction.unwrap_or(Direction::Down);
+    let ids: Vec<&str> = flowchart.nodes.keys().map(String::as_str).collect();
+    let index_of = |id: &str| ids.iter().position(|&n| n == id).expect("validated node ID");
+    let edges: Vec<(usize, usize)> = flowchart
+        .edges
+        .iter()
+        .map(|edge| (index_of(&edge.from), index_of(&edge.to)))
+        .collect();
+
+    let ranks = assign_ranks(ids.len(), &edges);
+    let layers = order_layers(&ranks, &edges);
+    let widths: Vec<f32> = flowchart
+        .nodes
+        .values()
+        .map(|label| (text_width(label.as_str(), FONT_SIZE) + NODE_PADDING).max(NODE_MIN_WIDTH))
+        .collect();
+
+    // Position nodes with layers stacked along the main axis and each layer
+    // centered on the cross axis
+    let mut boxes: Vec<NodeBox> = (0..ids.len())
+        .map(|_| NodeBox {
+            x: 0.0,
+            y: 0.0,
+            width: 0.0,
+        })
+        .collect();
+    let layer_extent = |layer: &[usize]| -> f32 {
+        let sizes = layer.iter().map(|&n| match direction {
+            Direction::Down => widths[n],
+            Direction::Right => NODE_HEIGHT,
+        });
+        sizes.sum::<f32>() + NODE_GAP * (layer.len().saturating_sub(1)) as f32
+    };
+    let cross_extent = layers
+        .iter()
+        .map(|layer| layer_extent(layer))
+        .fold(0.0, f32::max);
+    let mut main = MARGIN;
+    for (rank, layer) in layers.iter().enumerate() {
+        let mut cross = MARGIN + (cross_extent - layer_extent(layer)) / 2.0;
+        let mut layer_depth: f32 = 0.0;
+        for &n in layer {
+            let node = &mut boxes[n];
+            node.width = widths[n];
+            match direction {
+                Direction::Down => {
+                    node.x = cross;
+                    node.y = main;
+                    cross += widths[n] + NODE_GAP;
+                    layer_depth = NODE_HEIGHT;
+                }
+                Direction::Right => {
+                    node.x = main;
+                    node.y = cross;
+                    cross += NODE_HEIGHT + NODE_GAP;
+                    layer_depth = layer_depth.max(widths[n]);
+                }
+            }
+        }
+        // Labels sit across the gap when layers are laid out left to right
+        let label_depth = match direction {
+            Direction::Down => 0.0,
+            Direction::Right => flowchart
+                .edges
+                .iter()
+                .zip(&edges)
+                .filter(|(_, (from, to))| ranks[*from] == rank && ranks[*to] == rank + 1)
+                .filter_map(|(edge, _)| edge.label.as_ref())
+                .map(|label| text_width(label.as_str(), LABEL_FONT_SIZE) + 40.0)
+                .fold(0.0, f32::max),
+        };
+        main += layer_depth + LAYER_GAP.max(label_depth);
+    }
+    let main_extent = main - LAYER_GAP + MARGIN;
+
+    // Edges that don't connect adjacent layers are routed around the nodes,
+    // each in its own lane past the far side of the widest layer
+    let mut lane_count = 0;
+    let lanes: Vec<Option<usize>> = edges
+        .iter()
+        .map(|&(from, to)| {
+            (ranks[to] != ranks[from] + 1).then(|| {
+                lane_count += 1;
+                lane_count - 1
+            })
+        })
+        .collect();
+    let lane_label_extent = flowchart
+        .edges
+        .iter()
+        .zip(&lanes)
+        .filter(|(_, lane)| lane.is_some())
+        .filter_map(|(edge, _)| edge.label.as_ref())
+        .map(|label| match direction {
+            Direction::Down => text_width(label.as_str(), LABEL_FONT_SIZE) / 2.0 + 4.0,
+            Direction::Right => LABEL_FONT_SIZE / 2.0 + 3.0,
+        })
+        .fold(0.0, f32::max);
+    let lane_base = MARGIN + cross_extent;
+    let cross_extent = if lane_count == 0 {
+        cross_extent
+    } else {
+        cross_extent + LANE_GAP * lane_count as f32 + lane_label_extent
+    };
+    let (width, height) = match direction {
+        Direction::Down => (cross_extent + MARGIN * 2.0, main_extent),
+        Direction::Right => (main_extent, cross_extent + MARGIN * 2.0),
+    };
+
+    html! {
+        svg.diagram.diagram-flowchart xmlns="http://www.w3.org/2000/svg"
+            viewBox=(format!("0 0 {:.0} {:.0}", width, height))
+            width=(format!("{:.0}", width)) role="img" aria-label="Flowchart" {
+            @for ((edge, &(from, to)), lane) in flowchart.edges.iter().zip(&edges).zip(&lanes) {
+                @let label = edge.label.as_ref().map(|label| label.as_str());
+                @if let Some(lane) = lane {
+                    (render_lane_edge(&boxes[from], &boxes[to], lane_base + LANE_GAP * (*lane as f32 + 1.0), direction, label))
+                } @else {
+                    (render_layer_edge(&boxes[from], &boxes[to], direction, label))
+                }
+            }
+            @for (node, label) in boxes.iter().zip(flowchart.nodes.values()) {
+                g.diagram-node {
+                    rect x=(node.x) y=(node.y) width=(node.width) height=(NODE_HEIGHT) rx="6" {}
+                    text x=(node.center().x) y=(node.center().y) text-anchor="middle" dominant-baseline="central" { (label.as_str()) }
+                }
+            }
+        }
+    }
+}
+
+/// Draw an edge between adjacent layers as a curve between the facing sides
+/// of the two nodes.
+fn render_layer_edge(
+    from: &NodeBox,
+    to: &NodeBox,
+    direction: Direction,
+    label: Option<&str>,
+) -> Markup {
+    let (a, b) = (from.center(), to.center());
+    let (start, end, c1, c2) = match direction {
+        Direction::Down => {
+            let start = Point {
+                x: a.x,
+                y: a.y + NODE_HEIGHT / 2.0,
+            };
+            let end = Point {
+                x: b.x,
+                y: b.y - NODE_HEIGHT / 2.0,
+            };
+            let bend = (end.y - start.y) / 2.0;
+            (
+                start,
+                end,
+                Point {
+                    x: start.x,
+                    y: start.y + bend,
+                },
+                Point {
+                    x: end.x,
+                    y: end.y - bend,
+                },
+            )
+        }
+        Direction::Right => {
+            let start = Point {
+                x: a.x + from.width / 2.0,
+                y: a.y,
+            };
+            let end = Point {
+                x: b.x - to.width / 2.0,
+                y: b.y,
+            };
+            let bend = (end.x - start.x) / 2.0;
+            (
+                start,
+                end,
+                Point {
+                    x: start.x + bend,
+                    y: start.y,
+                },
+                Point {
+                    x: end.x - bend,
+                    y: end.y,
+                },
+            )
+        }
+    };
+    // Midpoint of the cubic Bézier curve
+    let middle = Point {
+        x: (start.x + 3.0 * c1.x + 3.0 * c2.x + end.x) / 8.0,
+        y: (start.y + 3.0 * c1.y + 3.0 * c2.y + end.y) / 8.0,
+    };
+
+    html! {
+        g.diagram-edge {
+            path d=(format!(
+                "M{:.1},{:.1} C{:.1},{:.1} {:.1},{:.1} {:.1},{:.1}",
+                start.x, start.y, c1.x, c1.y, c2.x, c2.y, end.x, end.y
+            )) {}
+            polygon.diagram-arrowhead points=(arrowhead(c2, end)) {}
+            @if let Some(label) = label {
+                (edge_label(label, middle))
+            }
+        }
+    }
+}
+
+/// Draw an edge that skips layers, points backwards or loops back to its
+/// node: out of the far side of `from`, along the lane at `lane` on the
+/// cross axis and into the far side of `to`.
+fn render_lane_edge(
+    from: &NodeBox,
+    to: &NodeBox,
+    lane: f32,
+    direction: Direction,
+    label: Option<&str>,
+) -> Markup {
+    // Separate the ends of a self-loop
+    let offset = if std::ptr::eq(from, to) {
+        NODE_HEIGHT / 4.0
+    } else {
+        0.0
+    };
+    let (a, b) = (from.center(), to.center());
+    let (path, end, toward, middle) = match direction {
+        Direction::Down => {
+            let (sx, sy) = (from.x + from.width, a.y - offset);
+            let (ex, ey) = (to.x + to.width, b.y + offset);
+            let r = LANE_RADIUS.min((ey - sy).abs() / 2.0) * (ey - sy).signum();
+            (
+                format!(
+                    "M{:.1},{:.1} H{:.1} Q{:.1},{:.1} {:.1},{:.1} V{:.1} Q{:.1},{:.1} {:.1},{:.1} H{:.1}",
+                    sx, sy, lane - LANE_RADIUS, lane, sy, lane, sy + r, ey - r, lane, ey, lane - LANE_RADIUS, ey, ex
+                ),
+                Point { x: ex, y: ey },
+                Point { x: ex + 1.0, y: ey },
+                Point {
+                    x: lane,
+                    y: (sy + ey) / 2.0,
+                },
+            )
+        }
+        Direction::Right => {
+            let (sx, sy) = (a.x - offset, from.y + NODE_HEIGHT);
+            let (ex, ey) = (b.x + offset, to.y + NODE_HEIGHT);
+            let r = LANE_RADIUS.min((ex - sx).abs() / 2.0) * (ex - sx).signum();
+            (
+                format!(
+                    "M{:.1},{:.1} V{:.1} Q{:.1},{:.1} {:.1},{:.1} H{:.1} Q{:.1},{:.1} {:.1},{:.1} V{:.1}",
+                    sx, sy, lane - LANE_RADIUS, sx, lane, sx + r, lane, ex - r, ex, lane, ex, lane - LANE_RADIUS, ey
+                ),
+                Point { x: ex, y: ey },
+                Point { x: ex, y: ey + 1.0 },
+                Point {
+                    x: (sx + ex) / 2.0,
+                    y: lane,
+                },
+            )
+        }
+    };
+
+    html! {
+        g.diagram-edge {
+            path d=(path) {}
+            polygon.diagram-arrowhead points=(arrowhead(toward, end)) {}
+            @if let Some(label) = label {
+                (edge_label(label, middle))
+            }
+        }
+    }
+}
+
+/// Assign each node a layer by longest path from the sources, ignoring the
+/// edges that close cycles.
+fn assign_ranks(count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    let back_edges = find_back_edges(count, edges);
+    let forward: Vec<(usize, usize)> = edges
+        .iter()
+        .enumerate()
+        .filter(|(i, (from, to))| !back_edges.contains(i) && from != to)
+        .map(|(_, &edge)| edge)
+        .collect();
+
+    // Bellman-Ford style relaxation; the graph is acyclic, so `count` rounds suffice
+    let mut ranks = vec![0; count];
+    for _ in 0..count {
+        let mut changed = false;
+        for &(from, to) in &forward {
+            if ranks[to] < ranks[from] + 1 {
+                ranks[to] = ranks[from] + 1;
+                changed = true;
+            }
+        }
+        if !changed {
+            break;
+        }
+    }
+    ranks
+}
+
+/// Indices of edges that close a cycle in a depth-first search from each node in order
+fn find_back_edges(count: usize, edges: &[(usize, usize)]) -> Vec<usize> {
+    #[derive(Clone, Copy, PartialEq)]
+    enum State {
+        Unvisited,
+        Active,
+        Done,
+    }
+
+    fn visit(
+        node: usize,
+        edges: &[(usize, usize)],
+        state: &mut [State],
+        back_edges: &mut Vec<usize>,
+    ) {
+        state[node] = State::Active;
+        for (i, &(from, to)) in edges.iter().enumerate() {
+            if from != node {
+                continue;
+            }
+            match state[to] {
+                State::Unvisited => visit(to, edges, state, back_edges),
+                State::Active => back_edges.push(i),
+                State::Done => {}
+            }
+        }
+        state[node] = State::Done;
+    }
+
+    let mut state = vec![State::Unvisited; count];
+    let mut back_edges = Vec::new();
+    for node in 0..count {
+        if state[node] == State::Unvisited {
+            visit(node, edges, &mut state, &mut back_edges);
+        }
+    }
+    back_edges
+}
+
+/// Group nodes into layers, ordering each layer by the average position of
+/// the node's predecessors to reduce crossings.
+fn order_layers(ranks: &[usize], edges: &[(usize, usize)]) -> Vec<Vec<usize>> {
+    let layer_count = ranks.iter().max().map_or(0, |max| max + 1);
+    let mut layers: Vec<Vec<usize>> = vec![Vec::new(); layer_count];
+    for (node, &rank) in ranks.iter().enumerate() {
+        layers[rank].push(node);
+    }
+
+    for rank in 1..layer_count {
+        let (previous, rest) = layers.split_at_mut(rank);
+        let previous = &previous[rank - 1];
+        let layer = &mut rest[0];
+        let barycenter = |node: usize| -> Option<f32> {
+            let positions: Vec<f32> = edges
+                .iter()
+                .filter(|&&(_, to)| to == node)
+                .filter_map(|&(from, _)| previous.iter().position(|&n| n == from))
+                .map(|p| p as f32)
+                .collect();
+            (!positions.is_empty()).then(|| positions.iter().sum::<f32>() / positions.len() as f32)
+        };
+        let keys: Vec<(usize, f32)> = layer
+            .iter()
+            .enumerate()
+            .map(|(i, &node)| (node, barycenter(node).unwrap_or(i as f32)))
+            .collect();
+        let mut sorted = keys;
+        sorted.sort_by(|a, b| a.1.total_cmp(&b.1));
+        *layer = sorted.into_iter().map(|(node, _)| node).collect();
+    }
+    layers
+}
+
+fn render_sequence(sequence: &Sequence) -> Markup {
+    let ids: Vec<&str> = sequence.participants.keys().map(String::as_str).collect();
+    let index_of = |id: &str| ids.iter().position(|&n| n == id).expect("validated participant ID");
+    let widths: Vec<f32> = sequence
+        .participants
+        .values()
+        .map(|label| (text_width(label.as_str(), FONT_SIZE) + NODE_PADDING).max(NODE_MIN_WIDTH))
+        .collect();
+
+    // Space adjacent lifelines so that the labels of messages between them fit
+    let mut centers = Vec::with_capacity(ids.len());
+    for i in 0..ids.len() {
+        let center = if i == 0 {
+            MARGIN + widths[0] / 2.0
+        } else {
+            let label_width = sequence
+                .messages
+                .iter()
+                .filter(|message| {
+                    let (a, b) = (index_of(&message.from), index_of(&message.to));
+                    a.min(b) == i - 1 && a.max(b) == i
+                })
+                .map(|message| text_width(message.label.as_str(), LABEL_FONT_SIZE) + 24.0)
+                .fold(0.0, f32::max);
+            centers[i - 1] + ((widths[i - 1] + widths[i]) / 2.0 + PARTICIPANT_GAP).max(label_width)
+        };
+        centers.push(center);
+    }
+    let self_label_width = sequence
+        .messages
+        .iter()
+        .filter(|message| message.from == message.to)
+        .map(|message| text_width(message.label.as_str(), LABEL_FONT_SIZE) + SELF_MESSAGE_WIDTH)
+        .fold(0.0, f32::max);
+    let width = centers[ids.len() - 1] + (widths[ids.len() - 1] / 2.0).max(self_label_width) + MARGIN;
+
+    let lifeline_top = MARGIN + NODE_HEIGHT;
+    let message_y = |i: usize| lifeline_top + MESSAGE_GAP * (i as f32 + 0.75);
+    let lifeline_bottom = message_y(sequence.messages.len()) - MESSAGE_GAP / 4.0;
+    let height = lifeline_bottom + MARGIN;
+
+    html! {
+        svg.diagram.diagram-sequence xmlns="http://www.w3.org/2000/svg"
+            viewBox=(format!("0 0 {:.0} {:.0}", width, height))
+            width=(format!("{:.0}", width)) role="img" aria-label="Sequence diagram" {
+            @for (i, label) in sequence.participants.values().enumerate() {
+                line.diagram-lifeline x1=(centers[i]) y1=(lifeline_top) x2=(centers[i]) y2=(lifeline_bottom) {}
+                g.diagram-node {
+                    rect x=(centers[i] - widths[i] / 2.0) y=(MARGIN) width=(widths[i]) height=(NODE_HEIGHT) rx="6" {}
+                    text x=(centers[i]) y=(MARGIN + NODE_HEIGHT / 2.0) text-anchor="middle" dominant-baseline="central" { (label.as_str()) }
+                }
+            }
+            @for (i, message) in sequence.messages.iter().enumerate() {
+                @let y = message_y(i);
+                @let (from, to) = (centers[index_of(&message.from)], centers[index_of(&message.to)]);
+                g.diagram-edge.diagram-reply[message.reply] {
+                    @if from == to {
+                        @let bottom = y + MESSAGE_GAP / 3.0;
+                        path d=(format!("M{:.1},{:.1} h{:.1} V{:.1} H{:.1}", from, y, SELF_MESSAGE_WIDTH, bottom, from)) {}
+                        polygon.diagram-arrowhead points=(arrowhead(Point { x: from + 1.0, y: bottom }, Point { x: from, y: bottom })) {}
+                        text.diagram-message-label x=(from + SELF_MESSAGE_WIDTH + 6.0) y=(y + MESSAGE_GAP / 6.0) dominant-baseline="central" { (message.label.as_str()) }
+                    } @else {
+                        path d=(format!("M{:.1},{:.1} H{:.1}", from, y, to)) {}
+                        polygon.diagram-arrowhead points=(arrowhead(Point { x: from, y }, Point { x: to, y })) {}
+                        text.diagram-message-label x=((from + to) / 2.0) y=(y - 8.0) text-anchor="middle" { (message.label.as_str()) }
+                    }
+                }
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn ranks_by_longest_path() {
+        // 0 -> 1 -> 2 and a shortcut 0 -> 2
+        assert_eq!(assign_ranks(3, &[(0, 1), (1, 2), (0, 2)]), [0, 1, 2]);
+        // Disconnected nodes stay in the first layer
+        assert_eq!(assign_ranks(3, &[(1, 2)]), [0, 0, 1]);
+    }
+
+    #[test]
+    fn ranks_ignore_cycles_and_self_loops() {
+        // 0 -> 1 -> 2 -> 0 closes a cycle, 1 -> 1 loops back to itself
+        let edges = [(0, 1), (1, 2), (2, 0), (1, 1)];
+        assert_eq!(find_back_edges(3, &edges), [2, 3]);
+        assert_eq!(assign_ranks(3, &edges), [0, 1, 2]);
+    }
+
+    #[test]
+    fn finds_no_back_edges_in_a_dag() {
+        assert!(find_back_edges(4, &[(0, 1), (0, 2), (1, 3), (2, 3)]).is_empty());
+    }
+
+    #[test]
+    fn orders_layers_by_predecessor_position() {
+        // Layer 0 is [0, 1]; node 2 follows 1 and node 3 follows 0, so they swap
+        let edges = [(1, 2), (0, 3)];
+        let ranks = assign_ranks(4, &edges);
+        assert_eq!(order_layers(&ranks, &edges), [vec![0, 1], vec![3, 2]]);
+    }
+
+    #[test]
+    fn keeps_order_of_nodes_without_predecessors_in_layer() {
+        let edges = [(0, 1), (0, 2)];
+        let ranks = assign_ranks(3, &edges);
+        assert_eq!(order_layers(&ranks, &edges), [vec![0], vec![1, 2]]);
+        assert!(order_layers(&[], &[]).is_empty());
+    }
+
+    #[test]
+    fn wide_characters_count_double() {
+        assert_eq!(text_width("ab", 10.0), 12.0);
+        assert_eq!(text_width("ユー", 10.0), 20.0);
+    }
+
+    #[test]
+    fn arrowhead_points_along_the_edge() {
+        let points = arrowhead(Point { x: 0.0, y: 0.0 }, Point { x: 0.0, y: 10.0 });
+        assert_eq!(points, "0.0,10.0 -4.5,2.0 4.5,2.0");
+    }
+}
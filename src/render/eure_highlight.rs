@@ -232,6 +232,14 @@ fn render_tokens_by_line(
     html! { (PreEscaped(result)) }
 }
 
+/// Highlight Eure source and split the markup into lines
+pub fn highlight_eure_lines(content: &str) -> Vec<String> {
+    render_eure_tokens_only(content)
+        .split('\n')
+        .map(str::to_string)
+        .collect()
+}
+
 /// Render eure tokens only (without pre/code wrapper) for nested code blocks
 fn render_eure_tokens_only(content: &str) -> String {
     let cst = match parse_tolerant(content) {
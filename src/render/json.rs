@@ -0,0 +1,15 @@
+use eure_json::{Config, document_to_value};
+use serde_json::Value;
+
+/// Convert an Eure snippet into pretty-printed JSON
+pub fn eure_to_json(source: &str) -> Result<String, String> {
+    serde_json::to_string_pretty(&eure_to_value(source)?).map_err(|e| e.to_string())
+}
+
+/// Convert an Eure snippet into a JSON value
+pub fn eure_to_value(source: &str) -> Result<Value, String> {
+    let cst = eure::parol::parse(source).map_err(|e| format!("Parse error: {:?}", e))?;
+    let doc = eure::document::cst_to_document(source, &cst)
+        .map_err(|e| format!("Document error: {:?}", e))?;
+    document_to_value(&doc, &Config::default()).map_err(|e| e.to_string())
+}
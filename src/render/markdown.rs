@@ -0,0 +1,536 @@
+use std::collections::HashMap;
+
+use markdown::{
+    Constructs, ParseOptions,
+    mdast::{AlignKind, Blockquote, ListItem, Node, Paragraph, Table},
+    to_mdast,
+};
+use maud::{Markup, PreEscaped, html};
+
+use crate::footnote::find_refs;
+use crate::render::{
+    alert::{alert_type, render_alert},
+    code_highlight::CodeHighlighter,
+    eure_highlight::render_eure_highlighted,
+    math::render_math,
+};
+use crate::site::Site;
+
+/// Markdown syntax accepted in articles: GFM plus `$...$` and `$$...$$`
+/// math. Prose that mentions a dollar sign escapes it as `\$`.
+pub fn markdown_parse_options() -> ParseOptions {
+    ParseOptions {
+        constructs: Constructs {
+            math_text: true,
+            math_flow: true,
+            ..Constructs::gfm()
+        },
+        math_text_single_dollar: true,
+        ..ParseOptions::gfm()
+    }
+}
+
+/// Parts of markdown that the page rendering it decides how to render
+pub trait MarkdownHooks {
+    /// Markup replacing the `<img>` of an image, such as a `<picture>` of an
+    /// article asset
+    fn image(&self, _src: &str, _alt: &str) -> Option<Markup> {
+        None
+    }
+    /// Markup replacing a `[^key]` footnote reference of prose
+    fn footnote_ref(&self, _key: &str) -> Option<Markup> {
+        None
+    }
+}
+
+/// Render markdown as written
+pub struct NoHooks;
+
+impl MarkdownHooks for NoHooks {}
+
+/// Render markdown content as flow content. Code fences are highlighted
+/// when a highlighter is given, blockquotes starting with a `[!TYPE]` marker
+/// become alerts, and headings get their ids and permalinks.
+pub fn render_markdown(
+    content: &str,
+    highlighter: Option<&CodeHighlighter>,
+    site: &Site,
+    hooks: &dyn MarkdownHooks,
+) -> Markup {
+    let root = parse(content);
+    let renderer = Renderer::new(&root, highlighter, site, hooks);
+    renderer.children(&root, false)
+}
+
+/// Render markdown content as phrasing content, for use inside paragraphs
+/// (e.g. sidenotes). Paragraph breaks become line breaks.
+pub fn render_markdown_inline(content: &str, site: &Site, hooks: &dyn MarkdownHooks) -> Markup {
+    let root = parse(content);
+    let renderer = Renderer::new(&root, None, site, hooks);
+    let blocks = root.children().map(Vec::as_slice).unwrap_or_default();
+    html! {
+        @for (i, block) in blocks.iter().enumerate() {
+            @if i > 0 {
+                br;
+            }
+            @if let Node::Paragraph(paragraph) = block {
+                (renderer.phrasing(&paragraph.children))
+            } @else {
+                (renderer.node(block, false))
+            }
+        }
+    }
+}
+
+/// Render prose, replacing its `[^key]` footnote references through `hooks`
+pub fn render_prose(text: &str, hooks: &dyn MarkdownHooks) -> Markup {
+    let mut last = 0;
+    let mut parts = Vec::new();
+    for (start, end, key) in find_refs(text) {
+        if let Some(markup) = hooks.footnote_ref(key) {
+            parts.push(html! { (text[last..start]) (markup) });
+            last = end;
+        }
+    }
+    html! {
+        @for part in parts {
+            (part)
+        }
+        (text[last..])
+    }
+}
+
+/// Convert the math of markdown content, so that invalid LaTeX fails the
+/// build instead of panicking mid-render
+pub fn check_markdown_math(content: &str) -> Result<(), String> {
+    fn check(node: &Node) -> Result<(), String> {
+        match node {
+            Node::Math(math) => render_math(&math.value, true).map(|_| ()),
+            Node::InlineMath(math) => render_math(&math.value, false).map(|_| ()),
+            Node::Code(code) if code.lang.as_deref() == Some("math") => {
+                render_math(&code.value, true).map(|_| ())
+            }
+            _ => node.children().into_iter().flatten().try_for_each(check),
+        }
+    }
+    check(&parse(content))
+}
+
+/// Heading of a markdown block, with the id it is rendered with
+pub struct MarkdownHeading {
+    pub depth: u8,
+    pub title: String,
+    pub id: String,
+}
+
+/// Collect the headings of markdown content in document order
+pub fn markdown_headings(content: &str) -> Vec<MarkdownHeading> {
+    fn collect(node: &Node, headings: &mut Vec<MarkdownHeading>) {
+        if let Node::Heading(heading) = node {
+            let title = node.to_string();
+            headings.push(MarkdownHeading {
+                depth: heading.depth,
+                id: heading_id(&title),
+                title,
+            });
+        }
+        for child in node.children().into_iter().flatten() {
+            collect(child, headings);
+        }
+    }
+    let mut headings = Vec::new();
+    collect(&parse(content), &mut headings);
+    headings
+}
+
+/// GitHub-style heading id: lowercase, punctuation dropped, spaces as hyphens.
+/// Titles without letters or digits give an empty id.
+fn heading_id(title: &str) -> String {
+    title
+        .trim()
+        .to_lowercase()
+        .chars()
+        .filter_map(|c| match c {
+            c if c.is_alphanumeric() || c == '-' || c == '_' => Some(c),
+            c if c.is_whitespace() => Some('-'),
+            _ => None,
+        })
+        .collect()
+}
+
+/// Permalink shown next to a heading
+pub fn header_anchor(id: &str) -> Markup {
+    html! {
+        a.header-anchor href=(format!("#{}", id)) aria-label="Permalink" { "#" }
+    }
+}
+
+fn parse(content: &str) -> Node {
+    to_mdast(content, &markdown_parse_options()).expect("markdown without MDX always parses")
+}
+
+/// Renders the syntax tree of one markdown text
+struct Renderer<'a> {
+    highlighter: Option<&'a CodeHighlighter>,
+    site: &'a Site,
+    hooks: &'a dyn MarkdownHooks,
+    /// URL and title of the link reference definitions, by identifier
+    definitions: HashMap<&'a str, (&'a str, Option<&'a str>)>,
+}
+
+impl<'a> Renderer<'a> {
+    fn new(
+        root: &'a Node,
+        highlighter: Option<&'a CodeHighlighter>,
+        site: &'a Site,
+        hooks: &'a dyn MarkdownHooks,
+    ) -> Self {
+        fn collect<'a>(
+            node: &'a Node,
+            definitions: &mut HashMap<&'a str, (&'a str, Option<&'a str>)>,
+        ) {
+            if let Node::Definition(definition) = node {
+                definitions
+                    .entry(&definition.identifier)
+                    .or_insert((&definition.url, definition.title.as_deref()));
+            }
+            for child in node.children().into_iter().flatten() {
+                collect(child, definitions);
+            }
+        }
+        let mut definitions = HashMap::new();
+        collect(root, &mut definitions);
+        Renderer {
+            highlighter,
+            site,
+            hooks,
+            definitions,
+        }
+    }
+
+    /// Render the children of a node. `tight` is set for the items of a
+    /// tight list, whose paragraphs are rendered without `<p>`.
+    fn children(&self, node: &Node, tight: bool) -> Markup {
+        self.nodes(
+            node.children().map(Vec::as_slice).unwrap_or_default(),
+            tight,
+        )
+    }
+
+    fn phrasing(&self, nodes: &[Node]) -> Markup {
+        self.nodes(nodes, false)
+    }
+
+    fn nodes(&self, nodes: &[Node], tight: bool) -> Markup {
+        html! {
+            @for node in nodes {
+                (self.node(node, tight))
+            }
+        }
+    }
+
+    fn node(&self, node: &Node, tight: bool) -> Markup {
+        match node {
+            Node::Paragraph(paragraph) => self.paragraph(paragraph, tight, None),
+            Node::Heading(heading) => {
+                let id = heading_id(&node.to_string());
+                let content = html! { (self.phrasing(&heading.children)) (header_anchor(&id)) };
+                match heading.depth {
+                    1 => html! { h1 id=(id) { (content) } },
+                    2 => html! { h2 id=(id) { (content) } },
+                    3 => html! { h3 id=(id) { (content) } },
+                    4 => html! { h4 id=(id) { (content) } },
+                    5 => html! { h5 id=(id) { (content) } },
+                    _ => html! { h6 id=(id) { (content) } },
+                }
+            }
+            Node::Blockquote(quote) => self.blockquote(quote),
+            Node::List(list) => {
+                let tight = !list.spread
+                    && list
+                        .children
+                        .iter()
+                        .all(|item| !matches!(item, Node::ListItem(item) if item.spread));
+                let items = html! {
+                    @for item in &list.children {
+                        @if let Node::ListItem(item) = item {
+                            (self.list_item(item, tight))
+                        }
+                    }
+                };
+                match (list.ordered, list.start) {
+                    (true, Some(start)) if start != 1 => html! { ol start=(start) { (items) } },
+                    (true, _) => html! { ol { (items) } },
+                    (false, _) => html! { ul { (items) } },
+                }
+            }
+            Node::Table(table) => self.table(table),
+            Node::ThematicBreak(_) => html! { hr; },
+            Node::Code(code) => match code.lang.as_deref() {
+                Some("math") => {
+                    render_math(&code.value, true).expect("math is validated before rendering")
+                }
+                Some(lang) => match self.highlighter {
+                    Some(_) if lang == "eure" => render_eure_highlighted(&code.value, None),
+                    Some(highlighter) => highlighter.highlight_or_plain(&code.value, lang),
+                    None => html! {
+                        pre { code class=(format!("language-{}", lang)) { (code_lines(&code.value)) } }
+                    },
+                },
+                None => html! { pre { code { (code_lines(&code.value)) } } },
+            },
+            Node::Math(math) => {
+                render_math(&math.value, true).expect("math is validated before rendering")
+            }
+            Node::InlineMath(math) => {
+                render_math(&math.value, false).expect("math is validated before rendering")
+            }
+            Node::Html(raw) => PreEscaped(raw.value.clone()),
+            Node::Text(text) => render_prose(&text.value, self.hooks),
+            Node::InlineCode(code) => html! { code { (code.value) } },
+            Node::Emphasis(emphasis) => html! { em { (self.phrasing(&emphasis.children)) } },
+            Node::Strong(strong) => html! { strong { (self.phrasing(&strong.children)) } },
+            Node::Delete(delete) => html! { del { (self.phrasing(&delete.children)) } },
+            Node::Break(_) => html! { br; "\n" },
+            Node::Link(link) => self.link(&link.url, link.title.as_deref(), &link.children),
+            Node::LinkReference(reference) => {
+                match self.definitions.get(reference.identifier.as_str()) {
+                    Some(&(url, title)) => self.link(url, title, &reference.children),
+                    None => self.phrasing(&reference.children),
+                }
+            }
+            Node::Image(image) => self.image(&image.url, &image.alt, image.title.as_deref()),
+            Node::ImageReference(reference) => {
+                match self.definitions.get(reference.identifier.as_str()) {
+                    Some(&(url, title)) => self.image(url, &reference.alt, title),
+                    None => html! { (reference.alt) },
+                }
+            }
+            // Footnotes are blocks of the article, not markdown; a `[^key]`
+            // that markdown took for a footnote is still a reference of prose
+            Node::FootnoteReference(reference) => {
+                render_prose(&format!("[^{}]", reference.identifier), self.hooks)
+            }
+            Node::FootnoteDefinition(_) => self.children(node, false),
+            _ => html! {},
+        }
+    }
+
+    /// Render a paragraph, with `prefix` before its content (the checkbox of
+    /// a task list item)
+    fn paragraph(&self, paragraph: &Paragraph, tight: bool, prefix: Option<Markup>) -> Markup {
+        let content = html! { @if let Some(prefix) = prefix { (prefix) } (self.phrasing(&paragraph.children)) };
+        if tight {
+            content
+        } else {
+            html! { p { (content) } }
+        }
+    }
+
+    fn list_item(&self, item: &ListItem, tight: bool) -> Markup {
+        let checkbox = item
+            .checked
+            .map(|checked| html! { input type="checkbox" disabled checked[checked]; " " });
+        match (checkbox, item.children.split_first()) {
+            (Some(checkbox), Some((Node::Paragraph(first), rest))) => html! {
+                li {
+                    (self.paragraph(first, tight, Some(checkbox)))
+                    (self.nodes(rest, tight))
+                }
+            },
+            (checkbox, _) => html! {
+                li {
+                    @if let Some(checkbox) = checkbox { (checkbox) }
+                    (self.nodes(&item.children, tight))
+                }
+            },
+        }
+    }
+
+    /// Render a blockquote, as an alert box when it starts with a `[!TYPE]`
+    /// marker alone on its first line
+    fn blockquote(&self, quote: &Blockquote) -> Markup {
+        let Some(alert) = alert_type(quote) else {
+            return html! { blockquote { (self.nodes(&quote.children, false)) } };
+        };
+        let (class_suffix, icon, title) = alert
+            .appearance(self.site)
+            .expect("GitHub alert types always have an appearance");
+        let Some((Node::Paragraph(first), rest)) = quote.children.split_first() else {
+            unreachable!("alerts start with a marker paragraph");
+        };
+        // The marker is the first line of the paragraph's first text
+        let content = html! {
+            @if let Some(Node::Text(text)) = first.children.first() {
+                @if let Some((_, after)) = text.value.split_once('\n') {
+                    p {
+                        (render_prose(after, self.hooks))
+                        (self.phrasing(&first.children[1..]))
+                    }
+                }
+            }
+            (self.nodes(rest, false))
+        };
+        render_alert(class_suffix, icon, html! { (title) }, content)
+    }
+
+    fn table(&self, table: &Table) -> Markup {
+        let align = |i: usize| match table.align.get(i) {
+            Some(AlignKind::Left) => Some("left"),
+            Some(AlignKind::Right) => Some("right"),
+            Some(AlignKind::Center) => Some("center"),
+            _ => None,
+        };
+        // Rows have as many cells as the delimiter row, padded or cut
+        fn cells(row: &Node, count: usize) -> impl Iterator<Item = Option<&Node>> {
+            let children = row.children().map(Vec::as_slice).unwrap_or_default();
+            (0..count).map(|i| children.get(i))
+        }
+        let Some((head, body)) = table.children.split_first() else {
+            return html! {};
+        };
+        html! {
+            table {
+                thead {
+                    tr {
+                        @for (i, cell) in cells(head, table.align.len()).enumerate() {
+                            th align=[align(i)] {
+                                @if let Some(cell) = cell { (self.children(cell, false)) }
+                            }
+                        }
+                    }
+                }
+                @if !body.is_empty() {
+                    tbody {
+                        @for row in body {
+                            tr {
+                                @for (i, cell) in cells(row, table.align.len()).enumerate() {
+                                    td align=[align(i)] {
+                                        @if let Some(cell) = cell { (self.children(cell, false)) }
+                                    }
+                                }
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+
+    fn link(&self, url: &str, title: Option<&str>, children: &[Node]) -> Markup {
+        html! { a href=(sanitize_url(url)) title=[title] { (self.phrasing(children)) } }
+    }
+
+    fn image(&self, url: &str, alt: &str, title: Option<&str>) -> Markup {
+        match self.hooks.image(url, alt) {
+            Some(markup) => markup,
+            None => html! { img src=(sanitize_url(url)) alt=(alt) title=[title]; },
+        }
+    }
+}
+
+/// Content of a code block, ending with a newline like its source lines
+fn code_lines(value: &str) -> String {
+    if value.is_empty() {
+        String::new()
+    } else {
+        format!("{}\n", value)
+    }
+}
+
+/// Drop URLs with a protocol other than the ones safe to link to, as the
+/// markdown HTML renderer does
+fn sanitize_url(url: &str) -> &str {
+    const SAFE_PROTOCOLS: [&str; 6] = ["http", "https", "mailto", "xmpp", "irc", "ircs"];
+    let protocol = url
+        .find([':', '/', '?', '#'])
+        .filter(|&end| url[end..].starts_with(':'))
+        .map(|end| &url[..end]);
+    match protocol {
+        Some(protocol)
+            if !SAFE_PROTOCOLS
+                .iter()
+                .any(|safe| safe.eq_ignore_ascii_case(protocol)) =>
+        {
+            ""
+        }
+        _ => url,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use super::*;
+    use crate::article::License;
+
+    fn render(content: &str) -> String {
+        let site = Site {
+            variables: IndexMap::new(),
+            alerts: IndexMap::new(),
+            license: License::parse_str("CC-BY-4.0").unwrap(),
+        };
+        render_markdown(content, None, &site, &NoHooks).into_string()
+    }
+
+    #[test]
+    fn renders_single_dollar_inline_math() {
+        let html = render("Let $x$ be a node.");
+        assert!(html.starts_with("<p>Let <math"), "{}", html);
+        assert!(html.contains("<mi>x</mi>"), "{}", html);
+        assert!(html.ends_with("</math> be a node.</p>"), "{}", html);
+        assert!(!html.contains("display=\"block\""), "{}", html);
+    }
+
+    #[test]
+    fn renders_double_dollar_math_as_display() {
+        let html = render("$$\na + b\n$$");
+        assert!(
+            html.starts_with("<div class=\"math-display\"><math"),
+            "{}",
+            html
+        );
+    }
+
+    #[test]
+    fn escaped_dollars_are_not_math() {
+        assert_eq!(render("costs \\$5 or \\$6"), "<p>costs $5 or $6</p>");
+    }
+
+    #[test]
+    fn heading_ids_follow_github() {
+        assert_eq!(heading_id("Hello World"), "hello-world");
+        assert_eq!(heading_id("  What's new in v1.2?  "), "whats-new-in-v12");
+        assert_eq!(
+            heading_id("snake_case and kebab-case"),
+            "snake_case-and-kebab-case"
+        );
+        assert_eq!(heading_id("A  B"), "a--b");
+    }
+
+    #[test]
+    fn heading_ids_keep_non_ascii_letters() {
+        assert_eq!(heading_id("Über Eure"), "über-eure");
+        assert_eq!(heading_id("ユーア 入門"), "ユーア-入門");
+    }
+
+    #[test]
+    fn heading_ids_of_punctuation_are_empty() {
+        assert_eq!(heading_id("!!!"), "");
+        assert_eq!(heading_id(""), "");
+    }
+
+    #[test]
+    fn headings_use_their_plain_text_for_ids() {
+        let headings = markdown_headings("# Intro\n\ntext\n\n## The `eure` *CLI*\n");
+        let ids: Vec<(u8, &str, &str)> = headings
+            .iter()
+            .map(|heading| (heading.depth, heading.title.as_str(), heading.id.as_str()))
+            .collect();
+        assert_eq!(
+            ids,
+            [(1, "Intro", "intro"), (2, "The eure CLI", "the-eure-cli")]
+        );
+    }
+}
@@ -0,0 +1,65 @@
+use latex2mathml::{DisplayStyle, latex_to_mathml};
+use maud::{html, Markup, PreEscaped};
+
+/// Render LaTeX into MathML at build time
+pub fn render_math(latex: &str, display: bool) -> Result<Markup, String> {
+    let style = if display {
+        DisplayStyle::Block
+    } else {
+        DisplayStyle::Inline
+    };
+    let mathml = latex_to_mathml(latex.trim(), style)
+        .map_err(|e| format!("invalid LaTeX in math `{}`: {}", latex.trim(), e))?;
+    if mathml.contains("[PARSE ERROR") {
+        return Err(format!("invalid LaTeX in math `{}`", latex.trim()));
+    }
+
+    let mathml = PreEscaped(escape_text_content(&mathml));
+    Ok(if display {
+        html! { div.math-display { (mathml) } }
+    } else {
+        mathml
+    })
+}
+
+/// latex2mathml emits operators such as `<` and `&` unescaped; escape
+/// anything in text content that would otherwise be read as markup.
+fn escape_text_content(mathml: &str) -> String {
+    let mut out = String::with_capacity(mathml.len());
+    let mut in_tag = false;
+    let mut chars = mathml.char_indices().peekable();
+    while let Some((i, c)) = chars.next() {
+        match c {
+            '<' if !in_tag => {
+                let next = chars.peek().map(|&(_, c)| c);
+                if next.is_some_and(|c| c.is_ascii_alphabetic() || c == '/') {
+                    in_tag = true;
+                    out.push(c);
+                } else {
+                    out.push_str("&lt;");
+                }
+            }
+            '>' if in_tag => {
+                in_tag = false;
+                out.push(c);
+            }
+            '>' => out.push_str("&gt;"),
+            '&' if !in_tag => {
+                let rest = &mathml[i + 1..];
+                let is_entity = rest.find(';').is_some_and(|end| {
+                    end > 0
+                        && rest[..end]
+                            .chars()
+                            .all(|c| c.is_ascii_alphanumeric() || c == '#')
+                });
+                if is_entity {
+                    out.push(c);
+                } else {
+                    out.push_str("&amp;");
+                }
+            }
+            _ => out.push(c),
+        }
+    }
+    out
+}
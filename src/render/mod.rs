@@ -1,6 +1,16 @@
+pub mod alert;
 pub mod code_highlight;
+pub mod diagnostics;
+pub mod diagram;
 pub mod eure_highlight;
+pub mod json;
+pub mod markdown;
+pub mod math;
+pub mod og_card;
 pub mod text;
 
 pub use code_highlight::CodeHighlighter;
-pub use text::render_text;
+pub use markdown::{
+    header_anchor, markdown_headings, markdown_parse_options, MarkdownHooks, NoHooks,
+};
+pub use text::{check_math, render_text, render_text_inline};
@@ -0,0 +1,273 @@
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+use resvg::{tiny_skia, usvg};
+use sha2::{Digest, Sha256};
+
+use crate::article::Article;
+use crate::asset::ImageAsset;
+
+pub const CARD_WIDTH: u32 = 1200;
+pub const CARD_HEIGHT: u32 = 630;
+
+/// Bump when the card layout changes so cached cards are re-rasterized
+const CARD_LAYOUT_VERSION: &str = "3";
+const CACHE_DIR: &str = ".cache/og-cards";
+/// Cards are set in vendored fonts so that they render the same on every machine
+const FONT_FAMILY: &str = "DejaVu Sans";
+const FONT_FILES: [&str; 2] = [
+    "assets/fonts/DejaVuSans.ttf",
+    "assets/fonts/DejaVuSans-Bold.ttf",
+];
+/// The Eure logo mark in `assets/`, drawn at the top left of every card
+const LOGO_FILE: &str = "eure-logo.svg";
+
+const TITLE_X: f32 = 80.0;
+const TITLE_MAX_WIDTH: f32 = 1040.0;
+const TITLE_MAX_LINES: usize = 3;
+const TITLE_FONT_SIZES: [f32; 3] = [64.0, 56.0, 48.0];
+
+/// Inputs of a social card; also the cache key
+struct CardInput<'a> {
+    title: &'a str,
+    date: Option<String>,
+    tags: &'a [String],
+}
+
+/// Rasterizes 1200x630 Open Graph cards for articles without a cover image:
+/// the Eure logo, the title, date and tags.
+pub struct OgCardRenderer {
+    options: usvg::Options<'static>,
+    /// Hash of the fonts and the logo, so that replacing one re-rasterizes the cards
+    assets_hash: Vec<u8>,
+}
+
+impl OgCardRenderer {
+    pub fn new() -> Result<Self, String> {
+        let mut hasher = Sha256::new();
+        let mut fontdb = usvg::fontdb::Database::new();
+        for file in FONT_FILES {
+            let data = fs::read(file).map_err(|e| format!("Failed to read {}: {}", file, e))?;
+            hasher.update(&data);
+            fontdb.load_font_data(data);
+        }
+        fontdb.set_sans_serif_family(FONT_FAMILY);
+        let logo_path = Path::new("assets").join(LOGO_FILE);
+        let logo = fs::read(&logo_path)
+            .map_err(|e| format!("Failed to read {}: {}", logo_path.display(), e))?;
+        hasher.update(&logo);
+
+        let options = usvg::Options {
+            resources_dir: Some(PathBuf::from("assets")),
+            font_family: FONT_FAMILY.to_string(),
+            fontdb: Arc::new(fontdb),
+            ..Default::default()
+        };
+
+        Ok(Self {
+            options,
+            assets_hash: hasher.finalize().to_vec(),
+        })
+    }
+
+    /// Write the card of an article to `dist/og/<slug>.png`, reusing the
+    /// cached PNG when the card inputs haven't changed.
+    pub fn generate(&self, slug: &str, article: &Article) -> Result<ImageAsset, String> {
+        let input = CardInput {
+            title: article.frontmatter.title.as_str(),
+            date: article.frontmatter.date.as_ref().map(|date| date.human()),
+            tags: &article.frontmatter.tags,
+        };
+
+        let cache_path = self.cache_path(&input);
+        if !cache_path.is_file() {
+            let png = self.render_png(&input)?;
+            fs::create_dir_all(CACHE_DIR).map_err(|e| e.to_string())?;
+            fs::write(&cache_path, png).map_err(|e| e.to_string())?;
+        }
+
+        let dest = Path::new("dist/og").join(format!("{}.png", slug));
+        fs::create_dir_all("dist/og").map_err(|e| e.to_string())?;
+        fs::copy(&cache_path, &dest).map_err(|e| e.to_string())?;
+
+        Ok(ImageAsset {
+            url: format!("/og/{}.png", slug),
+            width: CARD_WIDTH,
+            height: CARD_HEIGHT,
+            sources: Vec::new(),
+        })
+    }
+
+    fn cache_path(&self, input: &CardInput) -> PathBuf {
+        let mut hasher = Sha256::new();
+        hasher.update(&self.assets_hash);
+        for part in [
+            CARD_LAYOUT_VERSION,
+            input.title,
+            input.date.as_deref().unwrap_or(""),
+        ] {
+            hasher.update(part.as_bytes());
+            hasher.update([0]);
+        }
+        for tag in input.tags {
+            hasher.update(tag.as_bytes());
+            hasher.update([0]);
+        }
+        let hash: String = hasher
+            .finalize()
+            .iter()
+            .map(|b| format!("{:02x}", b))
+            .collect();
+        Path::new(CACHE_DIR).join(format!("{}.png", hash))
+    }
+
+    fn render_png(&self, input: &CardInput) -> Result<Vec<u8>, String> {
+        let svg = self.render_svg(input);
+        let tree = usvg::Tree::from_str(&svg, &self.options)
+            .map_err(|e| format!("Failed to parse OG card SVG: {}", e))?;
+        let mut pixmap = tiny_skia::Pixmap::new(CARD_WIDTH, CARD_HEIGHT)
+            .ok_or("Failed to allocate OG card pixmap")?;
+        resvg::render(&tree, tiny_skia::Transform::default(), &mut pixmap.as_mut());
+        pixmap
+            .encode_png()
+            .map_err(|e| format!("Failed to encode OG card: {}", e))
+    }
+
+    fn render_svg(&self, input: &CardInput) -> String {
+        let (font_size, lines) = self.layout_title(input.title);
+        let line_height = font_size * 1.25;
+        let title_top = 280.0 - (lines.len() as f32 - 1.0) * line_height / 2.0;
+
+        let mut svg = format!(
+            r##"<svg xmlns="http://www.w3.org/2000/svg" width="{w}" height="{h}" viewBox="0 0 {w} {h}">
+<rect width="{w}" height="{h}" fill="#1e1e2e"/>
+<rect x="0" y="0" width="16" height="{h}" fill="#cba6f7"/>
+<rect x="40" y="40" width="{iw}" height="{ih}" rx="24" fill="none" stroke="#313244" stroke-width="2"/>
+"##,
+            w = CARD_WIDTH,
+            h = CARD_HEIGHT,
+            iw = CARD_WIDTH - 80,
+            ih = CARD_HEIGHT - 80,
+        );
+
+        svg.push_str(&format!(
+            r##"<image x="{}" y="80" width="56" height="56" preserveAspectRatio="xMinYMid meet" href="{}"/>
+<text x="{}" y="124" font-size="40" font-weight="bold" fill="#cdd6f4">Eure</text>
+<text x="1120" y="120" font-size="28" fill="#7f849c" text-anchor="end">blog.eure.dev</text>
+"##,
+            TITLE_X,
+            LOGO_FILE,
+            TITLE_X + 72.0
+        ));
+
+        for (i, line) in lines.iter().enumerate() {
+            svg.push_str(&format!(
+                r##"<text x="{}" y="{}" font-size="{}" font-weight="bold" fill="#cdd6f4">{}</text>
+"##,
+                TITLE_X,
+                title_top + i as f32 * line_height,
+                font_size,
+                xml_escape(line)
+            ));
+        }
+
+        if let Some(date) = &input.date {
+            svg.push_str(&format!(
+                r##"<text x="{}" y="470" font-size="28" fill="#a6adc8">{}</text>
+"##,
+                TITLE_X,
+                xml_escape(date)
+            ));
+        }
+
+        let mut tag_x = TITLE_X;
+        for tag in input.tags {
+            let label = format!("#{}", tag);
+            let width = self.measure(&label, 24.0, false) + 32.0;
+            if tag_x + width > TITLE_X + TITLE_MAX_WIDTH {
+                break;
+            }
+            svg.push_str(&format!(
+                r##"<rect x="{x}" y="500" width="{width}" height="40" rx="8" fill="#313244"/>
+<text x="{tx}" y="528" font-size="24" fill="#bac2de">{label}</text>
+"##,
+                x = tag_x,
+                width = width,
+                tx = tag_x + 16.0,
+                label = xml_escape(&label)
+            ));
+            tag_x += width + 12.0;
+        }
+
+        svg.push_str("</svg>\n");
+        svg
+    }
+
+    /// Pick the largest font size at which the title wraps into at most
+    /// `TITLE_MAX_LINES` lines, truncating with an ellipsis as a last resort.
+    fn layout_title(&self, title: &str) -> (f32, Vec<String>) {
+        for font_size in TITLE_FONT_SIZES {
+            let lines = self.wrap(title, font_size);
+            if lines.len() <= TITLE_MAX_LINES {
+                return (font_size, lines);
+            }
+        }
+
+        let font_size = TITLE_FONT_SIZES[TITLE_FONT_SIZES.len() - 1];
+        let mut lines = self.wrap(title, font_size);
+        lines.truncate(TITLE_MAX_LINES);
+        if let Some(last) = lines.last_mut() {
+            while !last.is_empty()
+                && self.measure(&format!("{}…", last), font_size, true) > TITLE_MAX_WIDTH
+            {
+                last.pop();
+            }
+            last.push('…');
+        }
+        (font_size, lines)
+    }
+
+    fn wrap(&self, text: &str, font_size: f32) -> Vec<String> {
+        let mut lines = Vec::new();
+        let mut current = String::new();
+        for word in text.split_whitespace() {
+            let candidate = if current.is_empty() {
+                word.to_string()
+            } else {
+                format!("{} {}", current, word)
+            };
+            if !current.is_empty() && self.measure(&candidate, font_size, true) > TITLE_MAX_WIDTH
+            {
+                lines.push(std::mem::replace(&mut current, word.to_string()));
+            } else {
+                current = candidate;
+            }
+        }
+        if !current.is_empty() {
+            lines.push(current);
+        }
+        lines
+    }
+
+    /// Measure the rendered width of a single line of text
+    fn measure(&self, text: &str, font_size: f32, bold: bool) -> f32 {
+        let svg = format!(
+            r#"<svg xmlns="http://www.w3.org/2000/svg" width="{}" height="200"><text x="0" y="100" font-size="{}" font-weight="{}">{}</text></svg>"#,
+            CARD_WIDTH * 4,
+            font_size,
+            if bold { "bold" } else { "normal" },
+            xml_escape(text)
+        );
+        usvg::Tree::from_str(&svg, &self.options)
+            .map(|tree| tree.root().abs_bounding_box().width())
+            .unwrap_or(0.0)
+    }
+}
+
+fn xml_escape(s: &str) -> String {
+    s.replace('&', "&amp;")
+        .replace('<', "&lt;")
+        .replace('>', "&gt;")
+        .replace('"', "&quot;")
+}
@@ -1,34 +1,54 @@
 use eure::value::{Language, Text};
-use maud::{html, Markup, PreEscaped};
+use maud::{Markup, html};
 
-use crate::render::{code_highlight::CodeHighlighter, eure_highlight::render_eure_highlighted};
+use crate::render::{
+    code_highlight::CodeHighlighter,
+    eure_highlight::render_eure_highlighted,
+    markdown::{
+        MarkdownHooks, check_markdown_math, render_markdown, render_markdown_inline, render_prose,
+    },
+    math::render_math,
+};
+use crate::site::Site;
 
-pub fn render_text(text: &Text, highlighter: &CodeHighlighter) -> Markup {
+pub fn render_text(
+    text: &Text,
+    highlighter: &CodeHighlighter,
+    site: &Site,
+    hooks: &dyn MarkdownHooks,
+) -> Markup {
+    let content = text.as_str();
     match &text.language {
-        Language::Plaintext => html! { span.text-plain { (text.as_str()) } },
-        Language::Implicit => html! { code.code-inline { (text.as_str()) } },
-        Language::Other(lang) => render_with_language(text.as_str(), lang, highlighter),
+        Language::Plaintext => html! { span.text-plain { (render_prose(content, hooks)) } },
+        Language::Implicit => html! { code.code-inline { (content) } },
+        Language::Other(lang) if lang == "math" => {
+            let inline = text.syntax_hint.is_some_and(|hint| hint.is_inline());
+            render_math(content, !inline).expect("math is validated before rendering")
+        }
+        Language::Other(lang) if lang == "markdown" => html! {
+            div.markdown-content { (render_markdown(content, Some(highlighter), site, hooks)) }
+        },
+        Language::Other(lang) if lang == "eure" => render_eure_highlighted(content, None),
+        Language::Other(lang) => highlighter.highlight(content, lang),
     }
 }
 
-fn render_with_language(content: &str, lang: &str, highlighter: &CodeHighlighter) -> Markup {
-    match lang {
-        "markdown" => render_markdown(content),
-        "eure" => render_eure_highlighted(content, None),
-        other => highlighter.highlight(content, other),
+/// Render text as phrasing content, for use inside paragraphs (e.g. sidenotes).
+/// Paragraph breaks of markdown become line breaks.
+pub fn render_text_inline(text: &Text, site: &Site, hooks: &dyn MarkdownHooks) -> Markup {
+    let content = text.as_str();
+    if !text.language.is_other("markdown") {
+        return html! { (content) };
     }
+    render_markdown_inline(content, site, hooks)
 }
 
-fn render_markdown(content: &str) -> Markup {
-    use markdown::{to_html_with_options, CompileOptions, Options};
-    let options = Options {
-        compile: CompileOptions {
-            allow_dangerous_html: true,
-            ..CompileOptions::default()
-        },
-        ..Options::gfm()
-    };
-    let html_output =
-        to_html_with_options(content, &options).expect("Failed to convert markdown to HTML");
-    html! { div.markdown-content { (PreEscaped(html_output)) } }
+/// Convert the math of a math or markdown text, so that invalid LaTeX fails
+/// the build instead of panicking mid-render
+pub fn check_math(text: &Text) -> Result<(), String> {
+    match &text.language {
+        Language::Other(lang) if lang == "math" => render_math(text.as_str(), true).map(|_| ()),
+        Language::Other(lang) if lang == "markdown" => check_markdown_math(text.as_str()),
+        _ => Ok(()),
+    }
 }
@@ -0,0 +1,249 @@
+use std::collections::HashMap;
+
+use eure::{FromEure, value::Text};
+use indexmap::IndexMap;
+
+use crate::article::Article;
+
+/// Series metadata declared in `series.eure`
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Series {
+    pub title: Text,
+    pub description: Text,
+}
+
+/// A published article that belongs to a series
+#[derive(Debug, Clone, Copy)]
+pub struct SeriesPart<'a> {
+    pub slug: &'a str,
+    pub article: &'a Article,
+    /// The part number declared by the article; unnumbered parts continue
+    /// from the part before them
+    pub number: u32,
+}
+
+/// A series together with its published parts in reading order
+#[derive(Debug, Clone)]
+pub struct SeriesEntry<'a> {
+    pub id: &'a str,
+    pub series: &'a Series,
+    pub parts: Vec<SeriesPart<'a>>,
+}
+
+impl<'a> SeriesEntry<'a> {
+    /// Index of the article in reading order
+    pub fn position_of(&self, slug: &str) -> Option<usize> {
+        self.parts.iter().position(|part| part.slug == slug)
+    }
+}
+
+/// Position of an article within its series, used for in-article navigation
+#[derive(Debug, Clone, Copy)]
+pub struct SeriesPosition<'a> {
+    pub entry: &'a SeriesEntry<'a>,
+    pub index: usize,
+}
+
+impl<'a> SeriesPosition<'a> {
+    pub fn previous(&self) -> Option<&'a SeriesPart<'a>> {
+        self.index
+            .checked_sub(1)
+            .and_then(|i| self.entry.parts.get(i))
+    }
+
+    pub fn next(&self) -> Option<&'a SeriesPart<'a>> {
+        self.entry.parts.get(self.index + 1)
+    }
+}
+
+/// Group articles into their series, validating series IDs and part numbers.
+///
+/// Only series with at least one published part are returned, in the order
+/// they are declared in `series.eure`. Drafts are skipped before articles
+/// are grouped, so they are left out of series pages and the previous/next
+/// navigation, and a series whose parts are all drafts gets no page. Part
+/// numbers are not renumbered: a draft part leaves a gap in the labels of
+/// the published parts until it is published.
+pub fn collect_series<'a>(
+    catalog: &'a IndexMap<String, Series>,
+    articles: impl IntoIterator<Item = (&'a str, &'a Article)>,
+) -> Result<Vec<SeriesEntry<'a>>, String> {
+    let mut parts_by_id: HashMap<&str, Vec<(&'a str, &'a Article)>> = HashMap::new();
+
+    for (slug, article) in articles {
+        let Some(series_ref) = &article.frontmatter.series else {
+            continue;
+        };
+        if !catalog.contains_key(&series_ref.id) {
+            return Err(format!(
+                "{}: unknown series '{}' (not declared in series.eure)",
+                slug, series_ref.id
+            ));
+        }
+        parts_by_id
+            .entry(series_ref.id.as_str())
+            .or_default()
+            .push((slug, article));
+    }
+
+    let mut entries = Vec::new();
+    for (id, series) in catalog {
+        let Some(mut parts) = parts_by_id.remove(id.as_str()) else {
+            continue;
+        };
+        parts.sort_by(|(a_slug, a), (b_slug, b)| {
+            let a_part = part_number(a);
+            let b_part = part_number(b);
+            a_part
+                .is_none()
+                .cmp(&b_part.is_none())
+                .then(a_part.cmp(&b_part))
+                .then(a_slug.cmp(b_slug))
+        });
+
+        let mut seen_parts = HashMap::new();
+        for &(slug, article) in &parts {
+            if let Some(number) = part_number(article)
+                && let Some(other) = seen_parts.insert(number, slug)
+            {
+                return Err(format!(
+                    "Duplicate part {} in series '{}': {} and {}",
+                    number, id, other, slug
+                ));
+            }
+        }
+
+        let mut next_number = 1;
+        let parts = parts
+            .into_iter()
+            .map(|(slug, article)| {
+                let number = part_number(article).unwrap_or(next_number);
+                next_number = number + 1;
+                SeriesPart {
+                    slug,
+                    article,
+                    number,
+                }
+            })
+            .collect();
+
+        entries.push(SeriesEntry {
+            id: id.as_str(),
+            series,
+            parts,
+        });
+    }
+
+    Ok(entries)
+}
+
+fn part_number(article: &Article) -> Option<u32> {
+    article
+        .frontmatter
+        .series
+        .as_ref()
+        .and_then(|series_ref| series_ref.part)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn catalog() -> IndexMap<String, Series> {
+        let series = Series {
+            title: Text::plaintext("Query System Wins"),
+            description: Text::plaintext("A series"),
+        };
+        IndexMap::from([("query-system".to_string(), series)])
+    }
+
+    fn article(series: &str) -> Article {
+        crate::parse_article(&format!(
+            "$frontmatter {{\n  title: Part\n  date = `2026-01-01`\n  series {{\n{}\n  }}\n}}\n\n'#': Part\n",
+            series
+        ))
+        .unwrap()
+    }
+
+    fn slugs(entry: &SeriesEntry) -> Vec<String> {
+        entry.parts.iter().map(|part| part.slug.to_string()).collect()
+    }
+
+    #[test]
+    fn orders_numbered_parts_first() {
+        let catalog = catalog();
+        let articles = [
+            ("unnumbered-b", article("id: query-system")),
+            ("part-2", article("id: query-system\npart = 2")),
+            ("unnumbered-a", article("id: query-system")),
+            ("part-1", article("id: query-system\npart = 1")),
+        ];
+        let entries = collect_series(
+            &catalog,
+            articles.iter().map(|(slug, article)| (*slug, article)),
+        )
+        .unwrap();
+        assert_eq!(entries.len(), 1);
+        assert_eq!(
+            slugs(&entries[0]),
+            ["part-1", "part-2", "unnumbered-a", "unnumbered-b"]
+        );
+    }
+
+    #[test]
+    fn keeps_declared_part_numbers() {
+        let catalog = catalog();
+        let articles = [
+            ("part-3", article("id: query-system\npart = 3")),
+            ("unnumbered", article("id: query-system")),
+            ("part-1", article("id: query-system\npart = 1")),
+        ];
+        let entries = collect_series(
+            &catalog,
+            articles.iter().map(|(slug, article)| (*slug, article)),
+        )
+        .unwrap();
+        let numbers: Vec<u32> = entries[0].parts.iter().map(|part| part.number).collect();
+        assert_eq!(numbers, [1, 3, 4]);
+    }
+
+    #[test]
+    fn rejects_duplicate_parts() {
+        let catalog = catalog();
+        let articles = [
+            ("first", article("id: query-system\npart = 1")),
+            ("second", article("id: query-system\npart = 1")),
+        ];
+        let error = collect_series(
+            &catalog,
+            articles.iter().map(|(slug, article)| (*slug, article)),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "Duplicate part 1 in series 'query-system': first and second"
+        );
+    }
+
+    #[test]
+    fn rejects_unknown_series() {
+        let catalog = catalog();
+        let articles = [("stray", article("id: other"))];
+        let error = collect_series(
+            &catalog,
+            articles.iter().map(|(slug, article)| (*slug, article)),
+        )
+        .unwrap_err();
+        assert_eq!(
+            error,
+            "stray: unknown series 'other' (not declared in series.eure)"
+        );
+    }
+
+    #[test]
+    fn skips_series_without_parts() {
+        let catalog = catalog();
+        let entries = collect_series(&catalog, []).unwrap();
+        assert!(entries.is_empty());
+    }
+}
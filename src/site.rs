@@ -0,0 +1,393 @@
+use std::fs;
+use std::ops::Range;
+
+use eure::{
+    FromEure,
+    document::parse::{ParseContext, ParseError},
+    value::{Language, Text},
+};
+use indexmap::IndexMap;
+use markdown::{mdast::Node, to_mdast};
+
+use crate::article::{Article, Details, Figure, License, MarkOptions, SectionMark, VisitMut};
+use crate::render::markdown_parse_options;
+
+/// Colors of the Catppuccin palette, available as `--ctp-<name>` in `main.css`
+const PALETTE: &[&str] = &[
+    "rosewater",
+    "flamingo",
+    "pink",
+    "mauve",
+    "red",
+    "maroon",
+    "peach",
+    "yellow",
+    "green",
+    "teal",
+    "sky",
+    "sapphire",
+    "blue",
+    "lavender",
+    "text",
+    "subtext1",
+    "subtext0",
+    "overlay2",
+    "overlay1",
+    "overlay0",
+    "surface2",
+    "surface1",
+    "surface0",
+    "base",
+    "mantle",
+    "crust",
+];
+
+/// Site-wide settings declared in `site.eure`
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct Site {
+    /// License of the articles that don't declare `$license`
+    pub license: License,
+    /// Values substituted for `{{name}}` placeholders in article prose,
+    /// such as the current Eure version or the playground URL
+    #[eure(default)]
+    pub variables: IndexMap<String, String>,
+    /// Alert kinds in addition to the GitHub alert types, keyed by the name
+    /// used in `$mark.alert`
+    #[eure(default)]
+    pub alerts: IndexMap<String, AlertKind>,
+}
+
+/// Alert kind declared in `site.eure`
+#[derive(Debug, Clone, PartialEq, FromEure)]
+pub struct AlertKind {
+    /// Default title of alerts of this kind
+    pub title: String,
+    /// SVG icon inlined into the alert title, relative to the repository root
+    pub icon: AlertIcon,
+    /// Catppuccin palette color of the border and title, such as `teal`
+    pub color: String,
+}
+
+/// Icon of an alert kind: the path given in `site.eure`, and the SVG markup
+/// read from it by `Site::load_alerts`
+#[derive(Debug, Clone, PartialEq)]
+pub struct AlertIcon {
+    pub path: String,
+    pub svg: String,
+}
+
+impl FromEure<'_> for AlertIcon {
+    type Error = ParseError;
+
+    fn parse(ctx: &ParseContext<'_>) -> Result<Self, Self::Error> {
+        let path: &str = ctx.parse()?;
+        Ok(AlertIcon {
+            path: path.to_string(),
+            svg: String::new(),
+        })
+    }
+}
+
+impl Site {
+    /// Check the declared alert kinds and read their icons: names must be
+    /// usable as CSS classes, icons must be SVG files and colors must come
+    /// from the palette
+    pub fn load_alerts(&mut self) -> Result<(), String> {
+        for (name, kind) in &mut self.alerts {
+            if !name
+                .chars()
+                .all(|c| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-')
+            {
+                return Err(format!(
+                    "alert kind {} must consist of lowercase letters, digits and hyphens",
+                    name
+                ));
+            }
+            if !PALETTE.contains(&kind.color.as_str()) {
+                return Err(format!(
+                    "alert kind {}: unknown color {} (expected one of {})",
+                    name,
+                    kind.color,
+                    PALETTE.join(", ")
+                ));
+            }
+            let icon = &mut kind.icon;
+            let svg = fs::read_to_string(&icon.path)
+                .map_err(|e| format!("alert kind {}: icon {}: {}", name, icon.path, e))?;
+            if !svg.contains("<svg") {
+                return Err(format!(
+                    "alert kind {}: icon {} is not an SVG image",
+                    name, icon.path
+                ));
+            }
+            icon.svg = svg.trim_end().to_string();
+        }
+        Ok(())
+    }
+
+    /// Border and title colors of the declared alert kinds
+    pub fn alert_css(&self) -> String {
+        let mut css = String::new();
+        for (name, kind) in &self.alerts {
+            css.push_str(&format!(
+                "\n.alert-{name} {{\n    border-color: var(--ctp-{color});\n}}\n\n\
+                 .alert-{name} .alert-title {{\n    color: var(--ctp-{color});\n}}\n",
+                name = name,
+                color = kind.color
+            ));
+        }
+        if css.is_empty() {
+            css
+        } else {
+            format!("\n/* Site-defined alert kinds */{}", css)
+        }
+    }
+
+    /// Replace the `{{name}}` placeholders of every text the article page
+    /// renders with the site variables, once after the article is loaded:
+    /// the title, text blocks, headers, alert titles, details summaries,
+    /// figure captions and credits, and footnotes
+    pub fn expand_article(&self, article: &mut Article) -> Result<(), String> {
+        article.header = self
+            .expand_variables(&article.header)
+            .map_err(|e| format!("#: {}", e))?;
+        article.visit_mut(&mut ExpandVariables(self))
+    }
+
+    /// Replace the `{{name}}` placeholders of a text with the site variables.
+    /// Only prose is expanded: plaintext, and markdown outside of code and
+    /// math. `\{{name}}` stands for a literal `{{name}}`. Placeholders naming
+    /// an unknown variable are an error.
+    pub fn expand_variables(&self, text: &Text) -> Result<Text, String> {
+        let source = text.as_str();
+        let content = match &text.language {
+            Language::Plaintext => self.expand_prose(source)?,
+            Language::Other(lang) if lang == "markdown" => {
+                let mut output = String::with_capacity(source.len());
+                let mut last = 0;
+                for code in markdown_code_ranges(source) {
+                    output.push_str(&self.expand_prose(&source[last..code.start])?);
+                    output.push_str(&source[code.clone()]);
+                    last = code.end;
+                }
+                output.push_str(&self.expand_prose(&source[last..])?);
+                output
+            }
+            _ => return Ok(text.clone()),
+        };
+        Ok(Text {
+            content,
+            ..text.clone()
+        })
+    }
+
+    fn expand_prose(&self, source: &str) -> Result<String, String> {
+        let mut output = String::with_capacity(source.len());
+        let mut rest = source;
+        let mut unknown = Vec::new();
+        while let Some(start) = rest.find("{{") {
+            let after = &rest[start + 2..];
+            let name = after
+                .find("}}")
+                .map(|end| &after[..end])
+                .filter(|name| is_variable_name(name));
+            let Some(name) = name else {
+                output.push_str(&rest[..start + 2]);
+                rest = after;
+                continue;
+            };
+            if rest[..start].ends_with('\\') {
+                output.push_str(&rest[..start - 1]);
+                output.push_str(&rest[start..start + name.len() + 4]);
+            } else {
+                output.push_str(&rest[..start]);
+                match self.variables.get(name) {
+                    Some(value) => output.push_str(value),
+                    None => unknown.push(name),
+                }
+            }
+            rest = &after[name.len() + 2..];
+        }
+        output.push_str(rest);
+
+        if unknown.is_empty() {
+            Ok(output)
+        } else {
+            Err(format!(
+                "unknown site variable(s) not declared in site.eure: {}",
+                unknown.join(", ")
+            ))
+        }
+    }
+}
+
+struct ExpandVariables<'s>(&'s Site);
+
+impl ExpandVariables<'_> {
+    fn expand(&self, path: &str, text: &mut Text) -> Result<(), String> {
+        *text = self
+            .0
+            .expand_variables(text)
+            .map_err(|e| format!("{}: {}", path, e))?;
+        Ok(())
+    }
+
+    fn expand_details(&self, path: &str, details: Option<&mut Details>) -> Result<(), String> {
+        match details {
+            Some(details) => self.expand(path, &mut details.summary),
+            None => Ok(()),
+        }
+    }
+}
+
+impl VisitMut for ExpandVariables<'_> {
+    type Error = String;
+
+    fn visit_text(
+        &mut self,
+        path: &str,
+        text: &mut Text,
+        mark: Option<&mut MarkOptions>,
+    ) -> Result<(), String> {
+        self.expand(path, text)?;
+        if let Some(mark) = mark {
+            if let Some(title) = &mut mark.alert_title {
+                self.expand(path, title)?;
+            }
+            self.expand_details(path, mark.details.as_mut())?;
+        }
+        Ok(())
+    }
+
+    fn visit_header(
+        &mut self,
+        path: &str,
+        header: &mut Text,
+        mark: &mut SectionMark,
+    ) -> Result<(), String> {
+        self.expand(path, header)?;
+        self.expand_details(path, mark.details.as_mut())
+    }
+
+    fn visit_figure(&mut self, path: &str, figure: &mut Figure) -> Result<(), String> {
+        for text in [&mut figure.caption, &mut figure.credit].into_iter().flatten() {
+            self.expand(path, text)?;
+        }
+        Ok(())
+    }
+
+    fn visit_footnote(&mut self, key: &str, body: &mut Text) -> Result<(), String> {
+        self.expand(key, body)
+    }
+}
+
+/// Byte ranges of the code and math of markdown content, in document order
+fn markdown_code_ranges(content: &str) -> Vec<Range<usize>> {
+    fn collect(node: &Node, ranges: &mut Vec<Range<usize>>) {
+        match node {
+            Node::Code(_) | Node::InlineCode(_) | Node::Math(_) | Node::InlineMath(_) => {
+                if let Some(position) = node.position() {
+                    ranges.push(position.start.offset..position.end.offset);
+                }
+            }
+            _ => {
+                for child in node.children().into_iter().flatten() {
+                    collect(child, ranges);
+                }
+            }
+        }
+    }
+    let mut ranges = Vec::new();
+    if let Ok(root) = to_mdast(content, &markdown_parse_options()) {
+        collect(&root, &mut ranges);
+    }
+    ranges
+}
+
+/// Whether the name consists of ASCII letters, digits, `-` and `_` only
+pub fn is_variable_name(name: &str) -> bool {
+    !name.is_empty()
+        && name
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_')
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn site() -> Site {
+        Site {
+            variables: IndexMap::from([
+                ("eure-version".to_string(), "0.1.9".to_string()),
+                ("playground-url".to_string(), "https://eure.dev/play".to_string()),
+            ]),
+            alerts: IndexMap::new(),
+            license: License::parse_str("CC-BY-4.0").unwrap(),
+        }
+    }
+
+    fn expand(content: &str, language: Language) -> Result<String, String> {
+        site()
+            .expand_variables(&Text::new(content.to_string(), language))
+            .map(|text| text.content)
+    }
+
+    fn markdown() -> Language {
+        Language::Other("markdown".into())
+    }
+
+    #[test]
+    fn expands_plaintext() {
+        assert_eq!(
+            expand("Eure {{eure-version}} is out", Language::Plaintext).unwrap(),
+            "Eure 0.1.9 is out"
+        );
+    }
+
+    #[test]
+    fn skips_markdown_code_and_math() {
+        assert_eq!(
+            expand(
+                "Try <{{playground-url}}> or `{{eure-version}}` and $${{eure-version}}$$\n\n\
+                 ```yaml\nkey: ${{ runner.os }}-{{eure-version}}\n```\n",
+                markdown()
+            )
+            .unwrap(),
+            "Try <https://eure.dev/play> or `{{eure-version}}` and $${{eure-version}}$$\n\n\
+             ```yaml\nkey: ${{ runner.os }}-{{eure-version}}\n```\n"
+        );
+    }
+
+    #[test]
+    fn leaves_other_languages_untouched() {
+        assert_eq!(
+            expand("version = \"{{eure-version}}\"", Language::Other("eure".into())).unwrap(),
+            "version = \"{{eure-version}}\""
+        );
+    }
+
+    #[test]
+    fn escaped_placeholder_is_literal() {
+        assert_eq!(
+            expand("Write \\{{eure-version}} for {{eure-version}}", markdown()).unwrap(),
+            "Write {{eure-version}} for 0.1.9"
+        );
+    }
+
+    #[test]
+    fn ignores_braces_that_are_not_placeholders() {
+        assert_eq!(
+            expand("{{ not a name }} and {{}}", Language::Plaintext).unwrap(),
+            "{{ not a name }} and {{}}"
+        );
+    }
+
+    #[test]
+    fn unknown_variable_is_an_error() {
+        assert_eq!(
+            expand("{{eure-version}} {{missing}}", Language::Plaintext),
+            Err("unknown site variable(s) not declared in site.eure: missing".to_string())
+        );
+    }
+}
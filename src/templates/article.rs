@@ -1,25 +1,37 @@
-use std::collections::HashSet;
+use std::cell::{Cell, RefCell};
+use std::collections::{HashMap, HashSet};
 
 use eure::value::Text;
 use indexmap::IndexMap;
 use maud::{Markup, PreEscaped, html};
 
 use crate::article::{
-    AlertType, Article, Item, Level2, Level3, Level4, Level5, Level6, MarkOptions, TextOrNested,
-    TocEntry,
+    AlertType, Article, Details, Figure, Item, LicenseTerm, Level2, Level3, Level4, Level5, Level6, MarkOptions,
+    SectionMark, TextBlock, TextOrNested, TocEntry, Visit,
 };
-use crate::render::{CodeHighlighter, render_text};
-use crate::templates::base::{base_layout, OgpMeta, BASE_URL};
-
-// GitHub Octicons SVG icons for alerts
-const NOTE_ICON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" width="16" height="16" fill="currentColor"><path d="M0 8a8 8 0 1 1 16 0A8 8 0 0 1 0 8Zm8-6.5a6.5 6.5 0 1 0 0 13 6.5 6.5 0 0 0 0-13ZM6.5 7.75A.75.75 0 0 1 7.25 7h1a.75.75 0 0 1 .75.75v2.75h.25a.75.75 0 0 1 0 1.5h-2a.75.75 0 0 1 0-1.5h.25v-2h-.25a.75.75 0 0 1-.75-.75ZM8 6a1 1 0 1 1 0-2 1 1 0 0 1 0 2Z"/></svg>"#;
-const TIP_ICON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" width="16" height="16" fill="currentColor"><path d="M8 1.5c-2.363 0-4 1.69-4 3.75 0 .984.424 1.625.984 2.304l.214.253c.223.264.47.556.673.848.284.411.537.896.621 1.49a.75.75 0 0 1-1.484.211c-.04-.282-.163-.547-.37-.847a8.456 8.456 0 0 0-.542-.68c-.084-.1-.173-.205-.268-.32C3.201 7.75 2.5 6.766 2.5 5.25 2.5 2.31 4.863 0 8 0s5.5 2.31 5.5 5.25c0 1.516-.701 2.5-1.328 3.259-.095.115-.184.22-.268.319-.207.245-.383.453-.541.681-.208.3-.33.565-.37.847a.751.751 0 0 1-1.485-.212c.084-.593.337-1.078.621-1.489.203-.292.45-.584.673-.848.075-.088.147-.173.213-.253.561-.679.985-1.32.985-2.304 0-2.06-1.637-3.75-4-3.75ZM5.75 12h4.5a.75.75 0 0 1 0 1.5h-4.5a.75.75 0 0 1 0-1.5ZM6 15.25a.75.75 0 0 1 .75-.75h2.5a.75.75 0 0 1 0 1.5h-2.5a.75.75 0 0 1-.75-.75Z"/></svg>"#;
-const IMPORTANT_ICON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" width="16" height="16" fill="currentColor"><path d="M0 1.75C0 .784.784 0 1.75 0h12.5C15.216 0 16 .784 16 1.75v9.5A1.75 1.75 0 0 1 14.25 13H8.06l-2.573 2.573A1.458 1.458 0 0 1 3 14.543V13H1.75A1.75 1.75 0 0 1 0 11.25Zm1.75-.25a.25.25 0 0 0-.25.25v9.5c0 .138.112.25.25.25h2a.75.75 0 0 1 .75.75v2.19l2.72-2.72a.749.749 0 0 1 .53-.22h6.5a.25.25 0 0 0 .25-.25v-9.5a.25.25 0 0 0-.25-.25Zm7 2.25v2.5a.75.75 0 0 1-1.5 0v-2.5a.75.75 0 0 1 1.5 0ZM9 9a1 1 0 1 1-2 0 1 1 0 0 1 2 0Z"/></svg>"#;
-const WARNING_ICON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" width="16" height="16" fill="currentColor"><path d="M6.457 1.047c.659-1.234 2.427-1.234 3.086 0l6.082 11.378A1.75 1.75 0 0 1 14.082 15H1.918a1.75 1.75 0 0 1-1.543-2.575Zm1.763.707a.25.25 0 0 0-.44 0L1.698 13.132a.25.25 0 0 0 .22.368h12.164a.25.25 0 0 0 .22-.368Zm.53 3.996v2.5a.75.75 0 0 1-1.5 0v-2.5a.75.75 0 0 1 1.5 0ZM9 11a1 1 0 1 1-2 0 1 1 0 0 1 2 0Z"/></svg>"#;
-const CAUTION_ICON: &str = r#"<svg xmlns="http://www.w3.org/2000/svg" viewBox="0 0 16 16" width="16" height="16" fill="currentColor"><path d="M4.47.22A.749.749 0 0 1 5 0h6c.199 0 .389.079.53.22l4.25 4.25c.141.14.22.331.22.53v6a.749.749 0 0 1-.22.53l-4.25 4.25A.749.749 0 0 1 11 16H5a.749.749 0 0 1-.53-.22L.22 11.53A.749.749 0 0 1 0 11V5c0-.199.079-.389.22-.53Zm.84 1.28L1.5 5.31v5.38l3.81 3.81h5.38l3.81-3.81V5.31L10.69 1.5ZM8 4a.75.75 0 0 1 .75.75v3.5a.75.75 0 0 1-1.5 0v-3.5A.75.75 0 0 1 8 4Zm0 8a1 1 0 1 1 0-2 1 1 0 0 1 0 2Z"/></svg>"#;
+use crate::asset::{ArticleImages, ImageAsset};
+use crate::diagram::Diagram;
+use crate::examples::{Diagnostic, collect_diagnostics};
+use crate::excerpt::meta_description;
+use crate::footnote::{Footnotes, collect_footnotes};
+use crate::render::{
+    CodeHighlighter, MarkdownHooks, NoHooks, alert::render_alert, check_math,
+    diagnostics::render_diagnostics, diagram::render_diagram, header_anchor, json::eure_to_json,
+    markdown_headings, render_text, render_text_inline,
+};
+use crate::series::SeriesPosition;
+use crate::site::Site;
+use crate::templates::base::{base_layout, OgpImage, OgpMeta, BASE_URL};
+use crate::templates::picture::render_picture;
 
 /// Render text content with optional mark options (e.g., alert boxes)
-fn render_text_with_mark(text: &Text, mark: &MarkOptions, highlighter: &CodeHighlighter) -> Markup {
+fn render_text_with_mark(
+    path: &str,
+    text: &Text,
+    mark: &MarkOptions,
+    ctx: &RenderContext,
+) -> Markup {
+    let highlighter = ctx.highlighter;
     if mark.dangerously_inner_html {
         assert!(
             text.language.is_other("html"),
@@ -29,42 +41,109 @@ fn render_text_with_mark(text: &Text, mark: &MarkOptions, highlighter: &CodeHigh
         return html! { div.markdown-content { (PreEscaped(text.as_str())) } };
     }
 
-    let content = render_text(text, highlighter);
+    let mut content = if mark.show_diagnostics {
+        let diagnostics = ctx
+            .diagnostics
+            .get(path)
+            .expect("diagnostics must be collected before rendering");
+        render_diagnostics(text.as_str(), diagnostics)
+    } else {
+        render_block_text(text, ctx)
+    };
 
-    if let Some(alert_type) = &mark.alert {
-        let (class_suffix, icon, title) = match alert_type {
-            AlertType::Note => ("note", NOTE_ICON, "Note"),
-            AlertType::Tip => ("tip", TIP_ICON, "Tip"),
-            AlertType::Important => ("important", IMPORTANT_ICON, "Important"),
-            AlertType::Warning => ("warning", WARNING_ICON, "Warning"),
-            AlertType::Caution => ("caution", CAUTION_ICON, "Caution"),
+    if mark.show_json {
+        let json = eure_to_json(text.as_str()).expect("JSON views are validated before rendering");
+        content = html! {
+            div.json-view {
+                div.json-view-source { (content) }
+                div.json-view-json { (highlighter.highlight(&json, "json")) }
+            }
         };
+    }
 
-        html! {
-            div.alert class=(format!("alert-{}", class_suffix)) {
-                div.alert-title {
-                    span.alert-icon { (PreEscaped(icon)) }
-                    span.alert-title-text { (title) }
-                }
-                div.alert-content { (content) }
-            }
-        }
+    // Undeclared custom kinds fail `validate_alerts`; render them as plain content
+    let appearance = mark.alert.as_ref().and_then(|alert| alert.appearance(ctx.site));
+    let content = if let Some((class_suffix, icon, title)) = appearance {
+        let title = match &mark.alert_title {
+            Some(custom_title) => render_text_inline(custom_title, ctx.site, ctx),
+            None => html! { (title) },
+        };
+        render_alert(class_suffix, icon, title, content)
     } else {
         content
+    };
+
+    match &mark.details {
+        Some(details) => render_details(details, content, ctx),
+        None => content,
+    }
+}
+
+/// Collapse content behind its summary line, like the TOC
+fn render_details(details: &Details, content: Markup, ctx: &RenderContext) -> Markup {
+    html! {
+        details.content-details open[details.open] {
+            summary.content-details-summary { (render_text_inline(&details.summary, ctx.site, ctx)) }
+            div.content-details-body { (content) }
+        }
     }
 }
 
+/// Select the same tab in every code group and remember it for later visits
+const CODE_GROUP_SCRIPT: &str = r#"(() => {
+  const KEY = "code-group-tab";
+  const select = (label) => {
+    for (const input of document.querySelectorAll(".code-group-input")) {
+      if (input.value === label) input.checked = true;
+    }
+  };
+  try {
+    const saved = localStorage.getItem(KEY);
+    if (saved) select(saved);
+  } catch {}
+  document.addEventListener("change", (event) => {
+    const input = event.target;
+    if (!input.classList || !input.classList.contains("code-group-input")) return;
+    select(input.value);
+    try {
+      localStorage.setItem(KEY, input.value);
+    } catch {}
+  });
+})();"#;
+
 const GITHUB_REPO: &str = "eure-lang/blog.eure.dev";
 
 pub fn render_article_page(
     article: &Article,
     slug: &str,
+    series: Option<SeriesPosition>,
+    images: &ArticleImages,
     commit_hash: Option<&str>,
     highlighter: &CodeHighlighter,
+    site: &Site,
 ) -> Result<Markup, String> {
     // Collect TOC entries and validate unique IDs
     let mut seen_ids = HashSet::new();
-    let toc_entries = collect_toc_entries(&article.sections, &mut seen_ids)?;
+    let toc_entries = collect_toc_entries(&article.sections, 1, &mut seen_ids)?;
+    let footnotes = collect_footnotes(article)?;
+    validate_diagrams(article)?;
+    validate_json_views(article)?;
+    validate_math(article)?;
+    validate_alerts(article, site)?;
+    let description = meta_description(article)?;
+    let diagnostics = collect_diagnostics(article);
+
+    let ctx = RenderContext {
+        highlighter,
+        site,
+        toc_entries: &toc_entries,
+        figures: &images.figures,
+        inline_images: &images.inline,
+        code_group_count: Cell::new(0),
+        diagnostics: &diagnostics,
+        footnotes: &footnotes,
+        footnote_ref_counts: RefCell::new(HashMap::new()),
+    };
 
     let github_url = commit_hash.map(|hash| {
         format!(
@@ -73,13 +152,21 @@ pub fn render_article_page(
         )
     });
 
+    let license = article.license.as_ref().unwrap_or(&site.license);
+
+    let body = html! {
+        @for (key, item) in &article.sections {
+            (render_item_with_id("", key, item, &ctx))
+        }
+    };
+
     let content = html! {
         article.article {
             header.article-header {
-                h1.article-title { (render_text(&article.header, highlighter)) }
+                h1.article-title { (render_text(&article.header, highlighter, site, &NoHooks)) }
                 div.article-meta {
                     @if let Some(date) = &article.frontmatter.date {
-                        time.article-date { (date.as_str()) }
+                        time.article-date datetime=(date.datetime_attr()) { (date.human()) }
                     }
                     div.article-links {
                         a.article-source-link href=(format!("/source/{}.html", slug)) { "Source" }
@@ -95,10 +182,38 @@ pub fn render_article_page(
                         }
                     }
                 }
+                @if let (Some(image), Some(spec)) = (&images.cover, &article.frontmatter.cover) {
+                    (render_picture(image, spec.alt.as_str(), Some("article-cover"), false))
+                }
+            }
+            @if let Some(position) = &series {
+                (render_series_box(position))
+            }
+            div.article-content { (body) }
+            @if ctx.code_group_count.get() > 0 {
+                script { (PreEscaped(CODE_GROUP_SCRIPT)) }
+            }
+            @if !footnotes.notes.is_empty() {
+                (render_footnotes(&footnotes, highlighter, site))
             }
-            div.article-content {
-                @for (key, item) in &article.sections {
-                    (render_item_with_id(key, item, highlighter, &toc_entries))
+            @if let Some(position) = &series {
+                (render_series_nav(position))
+            }
+            footer.article-footer {
+                p.article-license {
+                    "This article is licensed under "
+                    @for term in license.terms() {
+                        @match term {
+                            LicenseTerm::Id(id) => {
+                                a href=(id.url()) rel="license" target="_blank" { (id.name()) }
+                            }
+                            LicenseTerm::And => " and ",
+                            LicenseTerm::Or => " or ",
+                            LicenseTerm::Open => "(",
+                            LicenseTerm::Close => ")",
+                        }
+                    }
+                    " (" (license) ")."
                 }
             }
         }
@@ -107,41 +222,237 @@ pub fn render_article_page(
     let url = format!("{}/articles/{}.html", BASE_URL, slug);
     let ogp = OgpMeta {
         title: article.frontmatter.title.as_str(),
-        description: article.frontmatter.description.as_str(),
+        description: &description,
         url: &url,
         og_type: "article",
+        license: Some(license),
+        image: images
+            .cover
+            .as_ref()
+            .zip(article.frontmatter.cover.as_ref())
+            .map(|(image, spec)| OgpImage {
+                url: &image.url,
+                width: image.width,
+                height: image.height,
+                alt: spec.alt.as_str(),
+            })
+            .or_else(|| {
+                images.og_card.as_ref().map(|image| OgpImage {
+                    url: &image.url,
+                    width: image.width,
+                    height: image.height,
+                    alt: article.frontmatter.title.as_str(),
+                })
+            }),
     };
     Ok(base_layout(article.frontmatter.title.as_str(), content, &ogp))
 }
 
+/// Render the box listing every part of the article's series
+fn render_series_box(position: &SeriesPosition) -> Markup {
+    let entry = position.entry;
+    html! {
+        aside.series-box {
+            p.series-box-title {
+                "Part " (entry.parts[position.index].number) " of "
+                a href=(format!("/series/{}.html", entry.id)) { (entry.series.title.as_str()) }
+            }
+            ol.series-parts {
+                @for (i, part) in entry.parts.iter().enumerate() {
+                    @if i == position.index {
+                        li.series-part.series-part-current value=(part.number) aria-current="page" {
+                            (part.article.frontmatter.title.as_str())
+                        }
+                    } @else {
+                        li.series-part value=(part.number) {
+                            a href=(format!("/articles/{}.html", part.slug)) {
+                                (part.article.frontmatter.title.as_str())
+                            }
+                        }
+                    }
+                }
+            }
+        }
+    }
+}
+
+/// Render previous/next part links at the end of a series article
+fn render_series_nav(position: &SeriesPosition) -> Markup {
+    html! {
+        nav.series-nav {
+            @if let Some(prev) = position.previous() {
+                a.series-nav-prev href=(format!("/articles/{}.html", prev.slug)) {
+                    span.series-nav-label { "← Previous part" }
+                    span.series-nav-title { (prev.article.frontmatter.title.as_str()) }
+                }
+            }
+            @if let Some(next) = position.next() {
+                a.series-nav-next href=(format!("/articles/{}.html", next.slug)) {
+                    span.series-nav-label { "Next part →" }
+                    span.series-nav-title { (next.article.frontmatter.title.as_str()) }
+                }
+            }
+        }
+    }
+}
+
+/// Check every diagram before rendering, so layout can rely on valid references
+fn validate_diagrams(article: &Article) -> Result<(), String> {
+    struct Validate(Result<(), String>);
+    impl<'a> Visit<'a> for Validate {
+        fn visit_diagram(&mut self, diagram: &'a Diagram) {
+            if self.0.is_ok() {
+                self.0 = diagram.validate();
+            }
+        }
+    }
+    let mut validate = Validate(Ok(()));
+    article.visit(&mut validate);
+    validate.0
+}
+
+/// Convert every snippet marked with `show-json` before rendering, so that
+/// conversion errors fail the build instead of panicking mid-render
+fn validate_json_views(article: &Article) -> Result<(), String> {
+    struct Validate(Result<(), String>);
+    impl<'a> Visit<'a> for Validate {
+        fn visit_text(&mut self, block: TextBlock<'a>) {
+            if self.0.is_err() || !block.mark.is_some_and(|mark| mark.show_json) {
+                return;
+            }
+            let text = block.text;
+            self.0 = if text.language.is_other("eure") {
+                eure_to_json(text.as_str())
+                    .map(|_| ())
+                    .map_err(|e| format!("{}: failed to convert Eure to JSON: {}", block.path, e))
+            } else {
+                Err(format!(
+                    "{}: show-json requires an Eure code block but got {:?}",
+                    block.path, text.language
+                ))
+            };
+        }
+    }
+    let mut validate = Validate(Ok(()));
+    article.visit(&mut validate);
+    validate.0
+}
+
+/// Every text the page renders, with the key path of its block, in document
+/// order: the title, text blocks, headers, alert titles, details summaries,
+/// figure captions and credits, and footnotes
+fn rendered_texts(article: &Article) -> Vec<(String, &Text)> {
+    struct Collect<'a>(Vec<(String, &'a Text)>);
+    impl<'a> Collect<'a> {
+        fn push_mark(&mut self, path: &str, mark: &'a MarkOptions) {
+            if let Some(title) = &mark.alert_title {
+                self.0.push((path.to_string(), title));
+            }
+            if let Some(details) = &mark.details {
+                self.0.push((path.to_string(), &details.summary));
+            }
+        }
+    }
+    impl<'a> Visit<'a> for Collect<'a> {
+        fn visit_text(&mut self, block: TextBlock<'a>) {
+            self.0.push((block.path.clone(), block.text));
+            if let Some(mark) = block.mark {
+                self.push_mark(&block.path, mark);
+            }
+        }
+        fn visit_header(&mut self, path: &str, header: &'a Text, mark: &'a SectionMark) {
+            self.0.push((path.to_string(), header));
+            if let Some(details) = &mark.details {
+                self.0.push((path.to_string(), &details.summary));
+            }
+        }
+        fn visit_figure(&mut self, path: &str, figure: &'a Figure) {
+            for text in [&figure.caption, &figure.credit].into_iter().flatten() {
+                self.0.push((path.to_string(), text));
+            }
+        }
+        fn visit_footnote(&mut self, key: &'a str, body: &'a Text) {
+            self.0.push((key.to_string(), body));
+        }
+    }
+    let mut collect = Collect(vec![("#".to_string(), &article.header)]);
+    article.visit(&mut collect);
+    collect.0
+}
+
+/// Convert the math of every text the page renders before rendering, so that
+/// invalid LaTeX fails the build with the path of its block
+fn validate_math(article: &Article) -> Result<(), String> {
+    for (path, text) in rendered_texts(article) {
+        check_math(text).map_err(|e| format!("{}: {}", path, e))?;
+    }
+    Ok(())
+}
+
+/// Check that every alert kind is either a GitHub alert type or declared in `site.eure`
+fn validate_alerts(article: &Article, site: &Site) -> Result<(), String> {
+    struct Validate<'s>(&'s Site, Result<(), String>);
+    impl<'a> Visit<'a> for Validate<'_> {
+        fn visit_text(&mut self, block: TextBlock<'a>) {
+            if self.1.is_err() {
+                return;
+            }
+            if let Some(alert @ AlertType::Custom(name)) =
+                block.mark.and_then(|mark| mark.alert.as_ref())
+                && alert.appearance(self.0).is_none()
+            {
+                self.1 = Err(format!(
+                    "{}: unknown alert kind {} (not a GitHub alert type nor declared in site.eure)",
+                    block.path, name
+                ));
+            }
+        }
+    }
+    let mut validate = Validate(site, Ok(()));
+    article.visit(&mut validate);
+    validate.1
+}
+
 /// Extract plain text from a Text value (strips any formatting)
 fn extract_plain_text(text: &Text) -> String {
     text.as_str().to_string()
 }
 
-/// Collect TOC entries from article sections, validating uniqueness
+/// Collect TOC entries from article sections, validating uniqueness.
+/// `level` is the level of the section the items belong to, 1 for the
+/// article itself.
 fn collect_toc_entries<T: CollectToc>(
     sections: &IndexMap<String, Item<TextOrNested<T>>>,
+    level: u8,
     seen_ids: &mut HashSet<String>,
 ) -> Result<Vec<TocEntry>, String> {
     let mut entries = Vec::new();
 
     for (id, item) in sections {
         match item {
-            Item::Normal(value) => {
-                if let Some(entry) = value.collect_toc_entry(id, seen_ids)? {
-                    entries.push(entry);
-                }
-            }
+            Item::Normal(value) => push_toc_entries(id, value, level, seen_ids, &mut entries)?,
             Item::List(items) => {
                 for value in items {
-                    if let Some(entry) = value.collect_toc_entry(id, seen_ids)? {
-                        entries.push(entry);
-                    }
+                    push_toc_entries(id, value, level, seen_ids, &mut entries)?;
                 }
             }
-            Item::Toc(_) => {
-                // TOC marker doesn't generate entries
+            Item::Partial(block) => {
+                let partial_entries =
+                    collect_toc_entries(&block.sections, level, seen_ids).map_err(|e| {
+                        format!(
+                            "{} in partial {} at {} (a partial with sections or headings \
+                             can only be used once per article)",
+                            e, block.partial, id
+                        )
+                    })?;
+                entries.extend(partial_entries);
+            }
+            Item::Toc(_)
+            | Item::Figure(_)
+            | Item::Footnote(_)
+            | Item::Diagram(_)
+            | Item::CodeGroup(_) => {
+                // TOC markers, figures, footnotes, diagrams and code groups don't generate entries
             }
         }
     }
@@ -169,17 +480,73 @@ impl CollectToc for Text {
     }
 }
 
-impl<T: CollectToc> CollectToc for TextOrNested<T> {
-    fn collect_toc_entry(
-        &self,
-        id: &str,
-        seen_ids: &mut HashSet<String>,
-    ) -> Result<Option<TocEntry>, String> {
-        match self {
-            TextOrNested::Text { .. } => Ok(None),
-            TextOrNested::Nested(nested) => nested.collect_toc_entry(id, seen_ids),
+/// Push the TOC entry of a nested section, or the entries of the headings
+/// of a markdown text
+fn push_toc_entries<T: CollectToc>(
+    id: &str,
+    value: &TextOrNested<T>,
+    level: u8,
+    seen_ids: &mut HashSet<String>,
+    entries: &mut Vec<TocEntry>,
+) -> Result<(), String> {
+    match value {
+        TextOrNested::Text { text, .. } => {
+            entries.extend(collect_heading_entries(text, level, seen_ids)?);
+        }
+        TextOrNested::Nested(nested) => {
+            if let Some(entry) = nested.collect_toc_entry(id, seen_ids)? {
+                entries.push(entry);
+            }
         }
     }
+    Ok(())
+}
+
+/// Collect TOC entries for the headings of a markdown text in a section of
+/// `level`, nested by level. A heading is one level below the section for
+/// each `#`; headings below level 3 are not included in the TOC but still
+/// need unique IDs.
+fn collect_heading_entries(
+    text: &Text,
+    level: u8,
+    seen_ids: &mut HashSet<String>,
+) -> Result<Vec<TocEntry>, String> {
+    let mut entries: Vec<TocEntry> = Vec::new();
+    if !text.language.is_other("markdown") {
+        return Ok(entries);
+    }
+    for heading in markdown_headings(text.as_str()) {
+        if heading.id.is_empty() {
+            return Err(format!(
+                "markdown heading \"{}\" has no letters or digits to derive its ID from",
+                heading.title
+            ));
+        }
+        if !seen_ids.insert(heading.id.clone()) {
+            return Err(format!(
+                "Duplicate section ID: {} (from markdown heading \"{}\")",
+                heading.id, heading.title
+            ));
+        }
+        let heading_level = level + heading.depth;
+        if heading_level > 3 {
+            continue;
+        }
+        let mut siblings = &mut entries;
+        while siblings
+            .last()
+            .is_some_and(|entry| entry.level < heading_level)
+        {
+            siblings = &mut siblings.last_mut().unwrap().children;
+        }
+        siblings.push(TocEntry {
+            id: heading.id,
+            title: heading.title,
+            level: heading_level,
+            children: Vec::new(),
+        });
+    }
+    Ok(entries)
 }
 
 impl CollectToc for Level2 {
@@ -191,7 +558,7 @@ impl CollectToc for Level2 {
         if !seen_ids.insert(id.to_string()) {
             return Err(format!("Duplicate section ID: {}", id));
         }
-        let children = collect_toc_entries(&self.sections, seen_ids)?;
+        let children = collect_toc_entries(&self.sections, 2, seen_ids)?;
         Ok(Some(TocEntry {
             id: id.to_string(),
             title: extract_plain_text(&self.header),
@@ -210,7 +577,7 @@ impl CollectToc for Level3 {
         if !seen_ids.insert(id.to_string()) {
             return Err(format!("Duplicate section ID: {}", id));
         }
-        let children = collect_toc_entries(&self.sections, seen_ids)?;
+        let children = collect_toc_entries(&self.sections, 3, seen_ids)?;
         Ok(Some(TocEntry {
             id: id.to_string(),
             title: extract_plain_text(&self.header),
@@ -231,7 +598,7 @@ impl CollectToc for Level4 {
         }
         // Level 4+ are not included in TOC but still need unique IDs
         // Recursively check children for duplicate IDs
-        collect_toc_entries(&self.sections, seen_ids)?;
+        collect_toc_entries(&self.sections, 4, seen_ids)?;
         Ok(None)
     }
 }
@@ -245,7 +612,7 @@ impl CollectToc for Level5 {
         if !seen_ids.insert(id.to_string()) {
             return Err(format!("Duplicate section ID: {}", id));
         }
-        collect_toc_entries(&self.sections, seen_ids)?;
+        collect_toc_entries(&self.sections, 5, seen_ids)?;
         Ok(None)
     }
 }
@@ -259,11 +626,19 @@ impl CollectToc for Level6 {
         if !seen_ids.insert(id.to_string()) {
             return Err(format!("Duplicate section ID: {}", id));
         }
-        // Level6 sections contain Text, check those for duplicate IDs
-        for (child_id, _) in &self.sections {
+        // Level6 sections contain Text, check those and their headings for duplicate IDs
+        for (child_id, item) in &self.sections {
             if !seen_ids.insert(child_id.to_string()) {
                 return Err(format!("Duplicate section ID: {}", child_id));
             }
+            let texts = match item {
+                Item::Normal(text) => std::slice::from_ref(text),
+                Item::List(texts) => texts.as_slice(),
+                _ => &[],
+            };
+            for text in texts {
+                collect_heading_entries(text, 6, seen_ids)?;
+            }
         }
         Ok(None)
     }
@@ -296,187 +671,306 @@ fn render_toc_list(entries: &[TocEntry]) -> Markup {
     }
 }
 
-/// Render an item with ID support for nested sections
-fn render_item_with_id<T>(
-    key: &str,
-    item: &Item<T>,
-    highlighter: &CodeHighlighter,
-    toc_entries: &[TocEntry],
-) -> Markup
+/// State shared while rendering the body of an article
+struct RenderContext<'a> {
+    highlighter: &'a CodeHighlighter,
+    site: &'a Site,
+    toc_entries: &'a [TocEntry],
+    /// Figure images keyed by key path, in document order
+    figures: &'a IndexMap<String, ImageAsset>,
+    /// Images of markdown content keyed by `src`
+    inline_images: &'a HashMap<String, ImageAsset>,
+    /// Number of code groups rendered so far, for unique radio group names
+    code_group_count: Cell<usize>,
+    /// Diagnostics of the blocks marked with `show-diagnostics`, keyed by key path
+    diagnostics: &'a HashMap<String, Vec<Diagnostic>>,
+    footnotes: &'a Footnotes<'a>,
+    /// Number of references rendered so far per footnote key
+    footnote_ref_counts: RefCell<HashMap<String, usize>>,
+}
+
+/// Render an item with ID support for nested sections. `parent` is the key
+/// path of the enclosing section, built the same way as `TextBlock::path`.
+fn render_item_with_id<T>(parent: &str, key: &str, item: &Item<T>, ctx: &RenderContext) -> Markup
 where
     T: RenderNestedWithId,
 {
+    let path = if parent.is_empty() {
+        key.to_string()
+    } else {
+        format!("{}.{}", parent, key)
+    };
     match item {
         Item::Normal(value) => html! {
             div.content-item data-key=(key) {
-                (value.render_with_id(key, highlighter, toc_entries))
+                (value.render_with_id(key, &path, ctx))
             }
         },
         Item::List(items) => html! {
             div.content-list data-key=(key) {
-                @for value in items {
+                @for (i, value) in items.iter().enumerate() {
                     div.content-list-item {
-                        (value.render_with_id(key, highlighter, toc_entries))
+                        (value.render_with_id(key, &format!("{}[{}]", path, i), ctx))
                     }
                 }
             }
         },
         Item::Toc(_) => {
-            if toc_entries.is_empty() {
+            if ctx.toc_entries.is_empty() {
                 html! {}
             } else {
-                render_toc(toc_entries)
+                render_toc(ctx.toc_entries)
+            }
+        }
+        Item::Figure(block) => render_figure(&path, &block.figure, ctx),
+        // Footnote bodies are rendered at their references and in the notes list
+        Item::Footnote(_) => html! {},
+        Item::Diagram(block) => render_diagram(&block.diagram),
+        Item::CodeGroup(block) => render_code_group(&block.code_group, ctx),
+        Item::Partial(block) => html! {
+            div.content-partial data-key=(key) data-partial=(block.partial) {
+                @for (key, item) in &block.sections {
+                    (render_item_with_id(&path, key, item, ctx))
+                }
+            }
+        },
+    }
+}
+
+/// Render snippets as tabs built from radio buttons, so switching works
+/// without JavaScript; `CODE_GROUP_SCRIPT` only syncs and remembers the choice.
+fn render_code_group(group: &IndexMap<String, Text>, ctx: &RenderContext) -> Markup {
+    let number = ctx.code_group_count.get() + 1;
+    ctx.code_group_count.set(number);
+    let name = format!("code-group-{}", number);
+
+    html! {
+        div.code-group role="group" {
+            @for (i, (label, text)) in group.iter().enumerate() {
+                @let id = format!("{}-{}", name, i + 1);
+                input.code-group-input type="radio" name=(name) id=(id) value=(label) checked[i == 0];
+                label.code-group-tab for=(id) { (label) }
+                div.code-group-panel { (render_block_text(text, ctx)) }
+            }
+        }
+    }
+}
+
+/// Render a numbered figure; figures are numbered in document order
+fn render_figure(path: &str, figure: &Figure, ctx: &RenderContext) -> Markup {
+    let (index, _, image) = ctx
+        .figures
+        .get_full(path)
+        .expect("figure images must be copied before rendering");
+    let number = index + 1;
+
+    html! {
+        figure.article-figure id=(format!("figure-{}", number)) {
+            (render_picture(image, figure.alt.as_str(), None, true))
+            figcaption {
+                span.figure-number { "Figure " (number) }
+                @if let Some(caption) = &figure.caption {
+                    div.figure-caption { (render_text(caption, ctx.highlighter, ctx.site, ctx)) }
+                }
+                @if let Some(credit) = &figure.credit {
+                    div.figure-credit { "Credit: " (render_text(credit, ctx.highlighter, ctx.site, ctx)) }
+                }
+            }
+        }
+    }
+}
+
+/// Render the numbered notes list at the end of the article
+fn render_footnotes(footnotes: &Footnotes, highlighter: &CodeHighlighter, site: &Site) -> Markup {
+    html! {
+        section.footnotes aria-label="Notes" {
+            ol {
+                @for note in &footnotes.notes {
+                    li id=(format!("fn-{}", note.key)) {
+                        (render_text(note.body, highlighter, site, &NoHooks))
+                        a.footnote-backref href=(format!("#fnref-{}", note.key)) aria-label="Back to reference" { "↩" }
+                    }
+                }
             }
         }
     }
 }
 
+/// Render a text block of the article body. Footnote references are only
+/// linked in plaintext and markdown blocks, the blocks `collect_footnotes`
+/// takes them from.
+fn render_block_text(text: &Text, ctx: &RenderContext) -> Markup {
+    render_text(text, ctx.highlighter, ctx.site, &BlockHooks(ctx))
+}
+
+/// Markdown images of the article body render as `<picture>` with their
+/// responsive sources
+impl MarkdownHooks for RenderContext<'_> {
+    fn image(&self, src: &str, alt: &str) -> Option<Markup> {
+        let image = self.inline_images.get(src)?;
+        Some(render_picture(image, alt, None, true))
+    }
+}
+
+/// Hooks of the text blocks of the article body, which also link footnote
+/// references
+struct BlockHooks<'c, 'a>(&'c RenderContext<'a>);
+
+impl MarkdownHooks for BlockHooks<'_, '_> {
+    fn image(&self, src: &str, alt: &str) -> Option<Markup> {
+        self.0.image(src, alt)
+    }
+
+    /// Superscript link to the note. The first reference of each footnote
+    /// also carries its body as a sidenote.
+    fn footnote_ref(&self, key: &str) -> Option<Markup> {
+        let ctx = self.0;
+        let note = ctx.footnotes.get(key)?;
+        let count = {
+            let mut ref_counts = ctx.footnote_ref_counts.borrow_mut();
+            let count = ref_counts.entry(note.key.to_string()).or_insert(0);
+            *count += 1;
+            *count
+        };
+        let ref_id = if count == 1 {
+            format!("fnref-{}", note.key)
+        } else {
+            format!("fnref-{}-{}", note.key, count)
+        };
+        Some(html! {
+            sup.footnote-ref id=(ref_id) {
+                a href=(format!("#fn-{}", note.key)) { (note.number) }
+            }
+            @if count == 1 {
+                span.sidenote role="note" {
+                    span.sidenote-number { (note.number) }
+                    " "
+                    (render_text_inline(note.body, ctx.site, ctx))
+                }
+            }
+        })
+    }
+}
+
 /// Trait for rendering nested content with ID support
 trait RenderNestedWithId {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup;
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup;
 }
 
 impl RenderNestedWithId for Text {
-    fn render_with_id(
-        &self,
-        _id: &str,
-        highlighter: &CodeHighlighter,
-        _toc_entries: &[TocEntry],
-    ) -> Markup {
-        render_text(self, highlighter)
+    fn render_with_id(&self, _id: &str, _path: &str, ctx: &RenderContext) -> Markup {
+        render_block_text(self, ctx)
     }
 }
 
 impl<T: RenderNestedWithId> RenderNestedWithId for TextOrNested<T> {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup {
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup {
         match self {
-            TextOrNested::Text { text, mark } => render_text_with_mark(text, mark, highlighter),
-            TextOrNested::Nested(nested) => nested.render_with_id(id, highlighter, toc_entries),
+            TextOrNested::Text { text, mark } => render_text_with_mark(path, text, mark, ctx),
+            TextOrNested::Nested(nested) => nested.render_with_id(id, path, ctx),
         }
     }
 }
 
 impl RenderNestedWithId for Level2 {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup {
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup {
         render_section_with_id(
             id,
+            path,
             &self.header,
+            &self.mark,
             &self.sections,
             "h2",
-            highlighter,
-            toc_entries,
+            ctx,
         )
     }
 }
 
 impl RenderNestedWithId for Level3 {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup {
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup {
         render_section_with_id(
             id,
+            path,
             &self.header,
+            &self.mark,
             &self.sections,
             "h3",
-            highlighter,
-            toc_entries,
+            ctx,
         )
     }
 }
 
 impl RenderNestedWithId for Level4 {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup {
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup {
         render_section_with_id(
             id,
+            path,
             &self.header,
+            &self.mark,
             &self.sections,
             "h4",
-            highlighter,
-            toc_entries,
+            ctx,
         )
     }
 }
 
 impl RenderNestedWithId for Level5 {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup {
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup {
         render_section_with_id(
             id,
+            path,
             &self.header,
+            &self.mark,
             &self.sections,
             "h5",
-            highlighter,
-            toc_entries,
+            ctx,
         )
     }
 }
 
 impl RenderNestedWithId for Level6 {
-    fn render_with_id(
-        &self,
-        id: &str,
-        highlighter: &CodeHighlighter,
-        toc_entries: &[TocEntry],
-    ) -> Markup {
+    fn render_with_id(&self, id: &str, path: &str, ctx: &RenderContext) -> Markup {
         render_section_with_id(
             id,
+            path,
             &self.header,
+            &self.mark,
             &self.sections,
             "h6",
-            highlighter,
-            toc_entries,
+            ctx,
         )
     }
 }
 
 fn render_section_with_id<T: RenderNestedWithId>(
     id: &str,
+    path: &str,
     header: &Text,
+    mark: &SectionMark,
     sections: &IndexMap<String, Item<T>>,
     level: &str,
-    highlighter: &CodeHighlighter,
-    toc_entries: &[TocEntry],
+    ctx: &RenderContext,
 ) -> Markup {
+    let highlighter = ctx.highlighter;
     let section_class = format!("article-section article-section-{}", level);
+    let items = html! {
+        @for (key, item) in sections {
+            (render_item_with_id(path, key, item, ctx))
+        }
+    };
     html! {
         section class=(section_class) {
             @match level {
-                "h2" => h2.section-header id=(id) { (render_text(header, highlighter)) },
-                "h3" => h3.section-header id=(id) { (render_text(header, highlighter)) },
-                "h4" => h4.section-header id=(id) { (render_text(header, highlighter)) },
-                "h5" => h5.section-header id=(id) { (render_text(header, highlighter)) },
-                "h6" => h6.section-header id=(id) { (render_text(header, highlighter)) },
-                _ => h2.section-header id=(id) { (render_text(header, highlighter)) },
-            }
-            @for (key, item) in sections {
-                (render_item_with_id(key, item, highlighter, toc_entries))
+                "h2" => h2.section-header id=(id) { (render_text(header, highlighter, ctx.site, ctx)) (header_anchor(id)) },
+                "h3" => h3.section-header id=(id) { (render_text(header, highlighter, ctx.site, ctx)) (header_anchor(id)) },
+                "h4" => h4.section-header id=(id) { (render_text(header, highlighter, ctx.site, ctx)) (header_anchor(id)) },
+                "h5" => h5.section-header id=(id) { (render_text(header, highlighter, ctx.site, ctx)) (header_anchor(id)) },
+                "h6" => h6.section-header id=(id) { (render_text(header, highlighter, ctx.site, ctx)) (header_anchor(id)) },
+                _ => h2.section-header id=(id) { (render_text(header, highlighter, ctx.site, ctx)) (header_anchor(id)) },
+            }
+            @match &mark.details {
+                Some(details) => (render_details(details, items, ctx)),
+                None => (items),
             }
         }
     }
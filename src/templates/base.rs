@@ -1,14 +1,28 @@
 use maud::{html, Markup, DOCTYPE};
 
+use crate::article::License;
+
 pub const BASE_URL: &str = "https://blog.eure.dev";
 pub const SITE_NAME: &str = "Eure Blog";
 pub const DEFAULT_DESCRIPTION: &str = "A blog about Eure, a data notation language for algebraic data types and deeply nested data.";
 
+/// Image shown in link previews
+pub struct OgpImage<'a> {
+    /// Site-absolute URL of the image
+    pub url: &'a str,
+    pub width: u32,
+    pub height: u32,
+    pub alt: &'a str,
+}
+
 pub struct OgpMeta<'a> {
     pub title: &'a str,
     pub description: &'a str,
     pub url: &'a str,
     pub og_type: &'a str,
+    pub license: Option<&'a License>,
+    /// Falls back to the site-wide `/ogp.png` when `None`
+    pub image: Option<OgpImage<'a>>,
 }
 
 pub fn base_layout(title: &str, content: Markup, ogp: &OgpMeta) -> Markup {
@@ -26,12 +40,29 @@ pub fn base_layout(title: &str, content: Markup, ogp: &OgpMeta) -> Markup {
                 meta property="og:url" content=(ogp.url);
                 meta property="og:type" content=(ogp.og_type);
                 meta property="og:site_name" content=(SITE_NAME);
-                meta property="og:image" content=(format!("{}/ogp.png", BASE_URL));
+                @if let Some(image) = &ogp.image {
+                    meta property="og:image" content=(format!("{}{}", BASE_URL, image.url));
+                    meta property="og:image:width" content=(image.width);
+                    meta property="og:image:height" content=(image.height);
+                    meta property="og:image:alt" content=(image.alt);
+                } @else {
+                    meta property="og:image" content=(format!("{}/ogp.png", BASE_URL));
+                }
                 // Twitter Card
                 meta name="twitter:card" content="summary_large_image";
                 meta name="twitter:title" content=(ogp.title);
                 meta name="twitter:description" content=(ogp.description);
-                meta name="twitter:image" content=(format!("{}/ogp.png", BASE_URL));
+                @if let Some(image) = &ogp.image {
+                    meta name="twitter:image" content=(format!("{}{}", BASE_URL, image.url));
+                    meta name="twitter:image:alt" content=(image.alt);
+                } @else {
+                    meta name="twitter:image" content=(format!("{}/ogp.png", BASE_URL));
+                }
+                @if let Some(license) = ogp.license {
+                    @for id in license.ids() {
+                        link rel="license" href=(id.url());
+                    }
+                }
                 // Favicon
                 link rel="icon" type="image/x-icon" href="/favicon.ico";
                 link rel="icon" type="image/png" sizes="32x32" href="/favicon-32x32.png";
@@ -1,11 +1,15 @@
 use maud::{html, Markup};
 
 use crate::article::Article;
+use crate::asset::ImageAsset;
+use crate::excerpt::article_excerpt;
 use crate::templates::base::{base_layout, OgpMeta, BASE_URL, DEFAULT_DESCRIPTION};
+use crate::templates::picture::render_picture;
 
 pub struct ArticleEntry<'a> {
     pub slug: &'a str,
     pub article: &'a Article,
+    pub cover: Option<&'a ImageAsset>,
 }
 
 pub fn render_index_page(articles: &[ArticleEntry]) -> Markup {
@@ -15,11 +19,19 @@ pub fn render_index_page(articles: &[ArticleEntry]) -> Markup {
             ul.article-list {
                 @for entry in articles {
                     li.article-list-item {
+                        @if let (Some(image), Some(spec)) = (entry.cover, &entry.article.frontmatter.cover) {
+                            a.article-thumbnail-link href=(format!("/articles/{}.html", entry.slug)) tabindex="-1" {
+                                (render_picture(image, spec.alt.as_str(), Some("article-thumbnail"), true))
+                            }
+                        }
                         a.article-link href=(format!("/articles/{}.html", entry.slug)) {
                             span.article-title { (entry.article.frontmatter.title.as_str()) }
                         }
                         @if let Some(date) = &entry.article.frontmatter.date {
-                            time.article-date { (date.as_str()) }
+                            time.article-date datetime=(date.datetime_attr()) { (date.human()) }
+                        }
+                        @if let Some(excerpt) = article_excerpt(entry.article) {
+                            p.article-excerpt { (excerpt) }
                         }
                         @if !entry.article.frontmatter.tags.is_empty() {
                             div.article-tags {
@@ -39,6 +51,8 @@ pub fn render_index_page(articles: &[ArticleEntry]) -> Markup {
         description: DEFAULT_DESCRIPTION,
         url: BASE_URL,
         og_type: "website",
+        license: None,
+        image: None,
     };
     base_layout("Home", content, &ogp)
 }
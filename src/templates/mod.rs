@@ -1,8 +1,11 @@
 pub mod article;
 pub mod base;
 pub mod index;
+pub mod picture;
+pub mod series;
 pub mod source;
 
 pub use article::render_article_page;
 pub use index::render_index_page;
+pub use series::render_series_page;
 pub use source::render_source_page;
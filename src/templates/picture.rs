@@ -0,0 +1,18 @@
+use maud::{html, Markup};
+
+use crate::asset::ImageAsset;
+
+/// Rendered width of images in the article column (see `.site-main` in main.css)
+const IMAGE_SIZES: &str = "(max-width: 900px) 100vw, 900px";
+
+/// Render an image as `<picture>` with its responsive sources, keeping the original as fallback
+pub fn render_picture(image: &ImageAsset, alt: &str, class: Option<&str>, lazy: bool) -> Markup {
+    html! {
+        picture {
+            @for source in &image.sources {
+                source type=(source.mime_type) srcset=(source.srcset) sizes=(IMAGE_SIZES);
+            }
+            img class=[class] src=(image.url) alt=(alt) width=(image.width) height=(image.height) loading=[lazy.then_some("lazy")];
+        }
+    }
+}
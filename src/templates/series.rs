@@ -0,0 +1,45 @@
+use maud::{html, Markup};
+
+use crate::excerpt::meta_description;
+use crate::series::SeriesEntry;
+use crate::templates::base::{base_layout, OgpMeta, BASE_URL};
+
+pub fn render_series_page(entry: &SeriesEntry) -> Markup {
+    let title = entry.series.title.as_str();
+    let content = html! {
+        div.series-page {
+            header.series-header {
+                p.series-kicker { "Series" }
+                h1.page-title { (title) }
+                p.series-description { (entry.series.description.as_str()) }
+            }
+            ol.article-list.series-article-list {
+                @for part in &entry.parts {
+                    li.article-list-item value=(part.number) {
+                        span.series-part-number { "Part " (part.number) }
+                        a.article-link href=(format!("/articles/{}.html", part.slug)) {
+                            span.article-title { (part.article.frontmatter.title.as_str()) }
+                        }
+                        @if let Some(date) = &part.article.frontmatter.date {
+                            time.article-date datetime=(date.datetime_attr()) { (date.human()) }
+                        }
+                        @if let Ok(description) = meta_description(part.article) {
+                            p.article-description { (description) }
+                        }
+                    }
+                }
+            }
+        }
+    };
+
+    let url = format!("{}/series/{}.html", BASE_URL, entry.id);
+    let ogp = OgpMeta {
+        title,
+        description: entry.series.description.as_str(),
+        url: &url,
+        og_type: "website",
+        license: None,
+        image: None,
+    };
+    base_layout(title, content, &ogp)
+}
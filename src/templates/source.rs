@@ -47,6 +47,8 @@ pub fn render_source_page(
         description: DEFAULT_DESCRIPTION,
         url: &url,
         og_type: "website",
+        license: None,
+        image: None,
     };
     base_layout(&format!("Source: {}", title), content, &ogp)
 }